@@ -1,27 +1,66 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 use reqwest::blocking::get;
 use serde_json::Value;
-use crate::CURRENCY_NAMES_CN;
+use crate::{currency_names, retirement, RETIRED_CURRENCIES};
+use crate::config::Config;
 use crate::model::CurrencyInfo;
+use crate::provider::{load_rates_file, run_rates_command};
 
 const API_URL: &str = "https://open.er-api.com/v6/latest/USD";
 const CACHE_HOURS: i64 = 12;
 
 // 获取汇率数据（带缓存）
-pub fn fetch_rates(cache_path: &PathBuf) -> Result<HashMap<String, CurrencyInfo>, String> {
+pub fn fetch_rates(cache_path: &Path, config: &Config) -> Result<HashMap<String, CurrencyInfo>, String> {
+    // 固定汇率模式：只读取指定文件
+    if let Some(path) = &config.rates_file {
+        return Ok(build_currencies(&load_rates_file(path)?, config));
+    }
+
+    let cache_path = &provider_cache_path(cache_path, config);
     if let Ok(metadata) = fs::metadata(cache_path) {
         if let Ok(modified) = metadata.modified() {
             let cache_time: DateTime<Utc> = modified.into();
             if Utc::now().signed_duration_since(cache_time).num_hours() < CACHE_HOURS {
                 let data = fs::read_to_string(cache_path).map_err(|e| e.to_string())?;
-                return serde_json::from_str(&data).map_err(|e| e.to_string());
+                let cached: HashMap<String, CurrencyInfo> = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+                // 名称按当前语言重新生成
+                let rates = cached.into_iter().map(|(code, info)| (code, info.rate)).collect();
+                return Ok(build_currencies(&rates, config));
             }
         }
     }
 
+    // 配置了外部命令时由命令提供汇率
+    let rates = match &config.rates_command {
+        Some(command) => run_rates_command(command, config.rates_command_timeout)?,
+        None => fetch_api_rates()?,
+    };
+    let currencies = build_currencies(&rates, config);
+
+    let json = serde_json::to_string(&currencies).map_err(|e| e.to_string())?;
+    fs::write(cache_path, json).map_err(|e| e.to_string())?;
+
+    Ok(currencies)
+}
+
+// 外部命令的汇率单独缓存，文件名带命令的哈希，换了汇率来源后不会读到其他来源的缓存
+fn provider_cache_path(cache_path: &Path, config: &Config) -> PathBuf {
+    match &config.rates_command {
+        Some(command) => {
+            let mut hasher = DefaultHasher::new();
+            command.hash(&mut hasher);
+            cache_path.with_file_name(format!("rates-{:016x}.json", hasher.finish()))
+        }
+        None => cache_path.to_path_buf(),
+    }
+}
+
+fn fetch_api_rates() -> Result<HashMap<String, f64>, String> {
     let response = get(API_URL)
         .map_err(|e| e.to_string())?
         .text()
@@ -30,22 +69,92 @@ pub fn fetch_rates(cache_path: &PathBuf) -> Result<HashMap<String, CurrencyInfo>
     let data: Value = serde_json::from_str(&response).map_err(|e| e.to_string())?;
     let rates = data["rates"].as_object().ok_or("无效API响应")?;
 
+    Ok(rates
+        .iter()
+        .filter_map(|(code, rate)| rate.as_f64().map(|rate| (code.clone(), rate)))
+        .collect())
+}
+
+// 汇率表附加配置语言的货币名称，缺少名称的货币以代码显示
+// 已停用的货币按固定比率由继任货币换算；汇率源缺少继任货币时，旧货币的汇率已不可靠，不再显示
+// 只有 1:1 挂钩的（如 ANG → XCG）可以反过来由旧货币得出继任货币
+fn build_currencies(rates: &HashMap<String, f64>, config: &Config) -> HashMap<String, CurrencyInfo> {
+    let mut rates = rates.clone();
+    for retired in RETIRED_CURRENCIES {
+        match (rates.get(retired.successor), rates.get(retired.code)) {
+            (Some(&rate), _) => rates.insert(retired.code.to_string(), rate * retired.per_successor),
+            (None, Some(&rate)) if retired.per_successor == 1.0 => rates.insert(retired.successor.to_string(), rate),
+            // 其他不显示旧汇率，查询时提示汇率源缺少继任货币
+            (None, _) => rates.remove(retired.code),
+        };
+    }
+
     let mut currencies = HashMap::new();
-    for (code, rate) in rates {
-        if let Some(rate) = rate.as_f64() {
-            if let Some(&(country, coin)) = CURRENCY_NAMES_CN
-                .iter()
-                .find(|&&(c, _)| c == code.as_str())
-                .map(|(_, names)| names) {
-                currencies.insert(
-                    code.clone(), CurrencyInfo::new(rate, country.to_string(), coin.to_string()),
-                );
+    let mut unnamed = Vec::new();
+    for (code, &rate) in &rates {
+        let mut info = match currency_names(code, config.language) {
+            Some((country, coin)) => CurrencyInfo::new(rate, country.to_string(), coin.to_string()),
+            None => {
+                unnamed.push(code.as_str());
+                CurrencyInfo::new(rate, String::new(), code.clone())
             }
-        }
+        };
+        info.retirement = retirement(code);
+        currencies.insert(code.clone(), info);
     }
 
-    let json = serde_json::to_string(&currencies).map_err(|e| e.to_string())?;
-    fs::write(cache_path, json).map_err(|e| e.to_string())?;
+    // 输出到 Alfred 调试窗口，便于补充名称
+    if !unnamed.is_empty() {
+        unnamed.sort();
+        eprintln!("缺少名称的货币: {}", unnamed.join(", "));
+    }
 
-    Ok(currencies)
-}
\ No newline at end of file
+    currencies
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::Path;
+    use crate::api::{build_currencies, provider_cache_path};
+    use crate::config::Config;
+    use crate::query::{CurrencyRef, ParseError};
+
+    #[test]
+    fn test_retired_currencies() {
+        let rates = HashMap::from([("EUR".to_string(), 0.9), ("HRK".to_string(), 7.0), ("ANG".to_string(), 1.79)]);
+        let currencies = build_currencies(&rates, &Config::default());
+
+        // 停用的货币按继任货币的固定比率换算，不用汇率源的旧汇率
+        let hrk = &currencies["HRK"];
+        assert!((hrk.rate - 0.9 * 7.5345).abs() < 1e-9);
+        assert_eq!(hrk.retirement.map(|r| r.successor), Some("EUR"));
+        assert!(currencies["EUR"].retirement.is_none());
+        assert!((currencies["BGN"].rate - 0.9 * 1.95583).abs() < 1e-9);
+
+        // 缺少继任货币时只有 1:1 挂钩的由旧货币得出，其他不显示旧汇率
+        assert_eq!(currencies["XCG"].rate, 1.79);
+        let rates = HashMap::from([("ZWL".to_string(), 13.0)]);
+        let currencies = build_currencies(&rates, &Config::default());
+        assert!(currencies.is_empty());
+        assert_eq!(
+            ParseError::missing_currency(&CurrencyRef::new("zwl", 5), &currencies).to_string(),
+            "ZWL 已于 2024-04-05 由 ZWG 取代，汇率源缺少 ZWG（位置 5）"
+        );
+    }
+
+    #[test]
+    fn test_provider_cache_path() {
+        let path = Path::new("cache/ratesUSD.json");
+        assert_eq!(provider_cache_path(path, &Config::default()), path);
+
+        // 不同的命令使用不同的缓存文件
+        let command = |c: &str| Config { rates_command: Some(c.into()), ..Config::default() };
+        let a = provider_cache_path(path, &command("treasury-rates"));
+        let b = provider_cache_path(path, &command("bank-rates"));
+        assert_ne!(a, path);
+        assert_ne!(a, b);
+        assert_eq!(a.parent(), path.parent());
+    }
+}
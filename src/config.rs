@@ -0,0 +1,124 @@
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const DEFAULT_COMMAND_TIMEOUT_SECS: u64 = 10;
+
+// 货币名称的显示语言
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    ZhHans,
+    ZhHant,
+    En,
+    Ja,
+}
+
+// 货币列表的排列方式，优先货币始终在前
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    Frecency,    // 按使用记录
+    Code,        // 按代码
+    ChineseName, // 按简体中文名称，汉字按 Unicode 顺序（部首笔画）
+    EnglishName, // 按英文名称
+    Region,      // 按地区，同一地区按代码
+}
+
+// 工作流配置，来自 Alfred 工作流环境变量
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub rates_command: Option<String>,  // 外部汇率命令
+    pub rates_command_timeout: Duration, // 外部汇率命令超时
+    pub rates_file: Option<PathBuf>,     // 固定汇率文件，设置后不访问网络和缓存
+    pub dollar_symbol: String,           // $ 对应的货币
+    pub yen_symbol: String,              // ¥ 对应的货币
+    pub decimal_separator: Option<char>, // 小数点，None 时自动判断
+    pub hours_per_day: f64,              // 每天工作小时数，用于时薪换算
+    pub days_per_week: f64,              // 每周工作天数
+    pub currency_aliases: Vec<(String, String)>, // 自定义别名（小写）和货币代码，优先于内置别名
+    pub language: Language,              // 货币名称的显示语言，搜索时匹配所有语言
+    pub sort_order: SortOrder,           // 货币列表的排列方式
+    pub data_dir: Option<PathBuf>,       // Alfred 工作流数据目录，保存使用记录
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            rates_command: None,
+            rates_command_timeout: Duration::from_secs(DEFAULT_COMMAND_TIMEOUT_SECS),
+            rates_file: None,
+            dollar_symbol: "USD".to_string(),
+            yen_symbol: "CNY".to_string(),
+            decimal_separator: None,
+            hours_per_day: 8.0,
+            days_per_week: 5.0,
+            currency_aliases: Vec::new(),
+            language: Language::ZhHans,
+            sort_order: SortOrder::Frecency,
+            data_dir: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            rates_command: var("rates_command"),
+            rates_command_timeout: var("rates_command_timeout")
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|s| *s > 0.0)
+                .map(Duration::from_secs_f64)
+                .unwrap_or(default.rates_command_timeout),
+            rates_file: var("rates_file").map(PathBuf::from),
+            dollar_symbol: var("dollar_symbol").map(|s| s.to_uppercase()).unwrap_or(default.dollar_symbol),
+            yen_symbol: var("yen_symbol").map(|s| s.to_uppercase()).unwrap_or(default.yen_symbol),
+            decimal_separator: match var("decimal_separator").as_deref() {
+                Some(".") => Some('.'),
+                Some(",") => Some(','),
+                _ => default.decimal_separator,
+            },
+            hours_per_day: positive("hours_per_day", 24.0).unwrap_or(default.hours_per_day),
+            days_per_week: positive("days_per_week", 7.0).unwrap_or(default.days_per_week),
+            currency_aliases: var("currency_aliases").map(|s| parse_aliases(&s)).unwrap_or_default(),
+            language: match var("language").map(|s| s.to_lowercase().replace('_', "-")).as_deref() {
+                Some("en" | "english") => Language::En,
+                Some("zh-hant" | "zh-tw" | "zh-hk" | "tw" | "繁體" | "繁体") => Language::ZhHant,
+                Some("ja" | "jp" | "japanese" | "日本語") => Language::Ja,
+                _ => default.language,
+            },
+            sort_order: match var("sort_order").map(|s| s.to_lowercase().replace(['_', '-'], " ")).as_deref() {
+                Some("code" | "代码") => SortOrder::Code,
+                Some("name" | "chinese" | "chinese name" | "中文" | "中文名称") => SortOrder::ChineseName,
+                Some("english" | "english name" | "英文" | "英文名称") => SortOrder::EnglishName,
+                Some("region" | "地区") => SortOrder::Region,
+                _ => default.sort_order,
+            },
+            data_dir: var("alfred_workflow_data").map(PathBuf::from),
+        }
+    }
+}
+
+// 解析别名配置，如 "lao=LAK, 卢比=INR"，以逗号、分号或换行分隔，忽略格式不对的项
+fn parse_aliases(value: &str) -> Vec<(String, String)> {
+    value
+        .split([',', ';', '\n', '，', '；'])
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(alias, code)| (alias.trim().to_lowercase(), code.trim().to_uppercase()))
+        .filter(|(alias, code)| !alias.is_empty() && code.len() == 3)
+        .collect()
+}
+
+// 读取不超过 max 的正数
+fn positive(name: &str, max: f64) -> Option<f64> {
+    var(name)
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|n| *n > 0.0 && *n <= max)
+}
+
+// 读取非空环境变量
+fn var(name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
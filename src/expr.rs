@@ -0,0 +1,330 @@
+// 金额表达式：支持 + - * / x × ÷ 和括号，例如 (120+35.5)*1.08、3x 49.99、1200/12
+// 数字可以是阿拉伯数字（可带数量级后缀）或中文数字，如 2.5k、5万、一百五十、壹佰伍拾
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Num(f64),
+    Op(char), // 统一为 + - * /
+    LParen,
+    RParen,
+}
+
+// 从输入开头扫描出最长的完整表达式，返回其结束位置（字节偏移），没有表达式时返回 0
+pub fn expression_end(input: &str) -> usize {
+    scan(input).1
+}
+
+// 计算表达式的值
+pub fn evaluate(expr: &str) -> Result<f64, String> {
+    let (tokens, end) = scan(expr);
+    if tokens.is_empty() {
+        return Err("缺少金额".to_string());
+    }
+    if !expr[end..].trim().is_empty() {
+        return Err(format!("无法识别的表达式: {}", expr[end..].trim()));
+    }
+
+    let mut parser = Parser { tokens: &tokens, pos: 0 };
+    let value = parser.expr()?;
+    if !value.is_finite() {
+        return Err("表达式结果无效".to_string());
+    }
+    Ok(value)
+}
+
+// 按语法逐个读取记号，遇到不能延续表达式的字符时停止
+fn scan(input: &str) -> (Vec<Token>, usize) {
+    let mut tokens = Vec::new();
+    let mut complete = (0, 0); // 最后一个完整表达式的记号数和结束位置
+    let mut expect_operand = true;
+    let mut depth = 0;
+    let mut pos = 0;
+
+    loop {
+        let rest = &input[pos..];
+        let start = pos + (rest.len() - rest.trim_start().len());
+        let rest = &input[start..];
+        let Some(c) = rest.chars().next() else { break };
+
+        let (token, len) = if expect_operand {
+            match c {
+                '(' => (Token::LParen, 1),
+                '+' | '-' => (Token::Op(c), 1),
+                '0'..='9' | '.' => match lex_number(rest) {
+                    Some((value, len)) => (Token::Num(value), len),
+                    None => break,
+                },
+                c if chinese_digit(c).is_some() || chinese_unit(c).is_some() => match lex_chinese_number(rest) {
+                    Some((value, len)) => (Token::Num(value), len),
+                    None => break,
+                },
+                _ => break,
+            }
+        } else {
+            match c {
+                '+' | '-' | '*' | '/' => (Token::Op(c), 1),
+                '×' => (Token::Op('*'), c.len_utf8()),
+                '÷' => (Token::Op('/'), c.len_utf8()),
+                // x 仅在后面紧跟数字或括号时视为乘号，避免吞掉 xaf 等货币代码
+                'x' if rest[1..].trim_start().starts_with(|n: char| n.is_ascii_digit() || n == '(' || n == '.') => {
+                    (Token::Op('*'), 1)
+                }
+                ')' if depth > 0 => (Token::RParen, 1),
+                _ => break,
+            }
+        };
+
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth -= 1,
+            _ => {}
+        }
+        expect_operand = matches!(token, Token::Op(_) | Token::LParen);
+        tokens.push(token);
+        pos = start + len;
+
+        if !expect_operand && depth == 0 {
+            complete = (tokens.len(), pos);
+        }
+    }
+
+    tokens.truncate(complete.0);
+    (tokens, complete.1)
+}
+
+// 数量级后缀，英文后缀需与后面的字母隔开，中文单位可连写（如 5千万）
+const MAGNITUDES: &[(&str, f64)] = &[
+    ("bn", 1e9),
+    ("k", 1e3),
+    ("m", 1e6),
+    ("千", 1e3),
+    ("万", 1e4),
+    ("亿", 1e8),
+];
+
+// 读取数字字面量（含数量级后缀），返回值和长度
+fn lex_number(input: &str) -> Option<(f64, usize)> {
+    let mut len = input
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(input.len());
+    let mut value = input[..len].parse::<f64>().ok()?;
+
+    while let Some((suffix, factor)) = MAGNITUDES.iter().find(|(suffix, _)| {
+        let rest = &input[len..];
+        rest.starts_with(suffix)
+            && (!suffix.is_ascii() || !rest[suffix.len()..].starts_with(|c: char| c.is_ascii_alphabetic()))
+    }) {
+        value *= factor;
+        len += suffix.len();
+        // 英文后缀只取一个
+        if suffix.is_ascii() {
+            break;
+        }
+    }
+
+    Some((value, len))
+}
+
+fn chinese_digit(c: char) -> Option<u32> {
+    match c {
+        '零' | '〇' => Some(0),
+        '一' | '壹' => Some(1),
+        '二' | '两' | '贰' => Some(2),
+        '三' | '叁' => Some(3),
+        '四' | '肆' => Some(4),
+        '五' | '伍' => Some(5),
+        '六' | '陆' => Some(6),
+        '七' | '柒' => Some(7),
+        '八' | '捌' => Some(8),
+        '九' | '玖' => Some(9),
+        _ => None,
+    }
+}
+
+fn chinese_unit(c: char) -> Option<f64> {
+    match c {
+        '十' | '拾' => Some(1e1),
+        '百' | '佰' => Some(1e2),
+        '千' | '仟' => Some(1e3),
+        '万' => Some(1e4),
+        '亿' => Some(1e8),
+        _ => None,
+    }
+}
+
+// 读取中文数字，如 一百五十、三千二百、两万五（= 25000）、三点五，返回值和长度
+fn lex_chinese_number(input: &str) -> Option<(f64, usize)> {
+    let mut total = 0.0; // 已完成的万、亿段
+    let mut section = 0.0; // 万以下的部分
+    let mut pending: Option<f64> = None; // 尚未乘单位的数字
+    let mut last_unit = 1.0;
+    let mut digits_after_unit = 0; // 用于口语省略，如 两万五
+    let mut has_digit = false;
+    let mut len = 0;
+
+    let mut chars = input.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if let Some(digit) = chinese_digit(c) {
+            // 连续数字按位读，如 一二三
+            pending = Some(pending.unwrap_or(0.0) * 10.0 + digit as f64);
+            digits_after_unit += if digit == 0 { 2 } else { 1 };
+            has_digit = true;
+        } else if let Some(unit) = chinese_unit(c) {
+            let n = pending.take().unwrap_or(if unit < 1e4 { 1.0 } else { 0.0 });
+            if unit < 1e4 {
+                section += n * unit;
+            } else if unit == 1e4 {
+                total += (section + n) * unit;
+                section = 0.0;
+            } else {
+                total = (total + section + n) * unit;
+                section = 0.0;
+            }
+            last_unit = unit;
+            digits_after_unit = 0;
+        } else if c == '点' && chars.peek().is_some_and(|&(_, n)| chinese_digit(n).is_some()) {
+            // 小数部分逐位读取
+            let mut scale = 0.1;
+            let mut fraction = 0.0;
+            while let Some(&(index, n)) = chars.peek() {
+                let Some(digit) = chinese_digit(n) else { break };
+                fraction += digit as f64 * scale;
+                scale /= 10.0;
+                len = index + n.len_utf8();
+                chars.next();
+            }
+            section += pending.take().unwrap_or(0.0) + fraction;
+            break;
+        } else {
+            break;
+        }
+        len = index + c.len_utf8();
+    }
+
+    // 单独的百、千、万等不视为数字，避免吞掉 百慕大 之类的名称
+    if !has_digit && last_unit != 1e1 {
+        return None;
+    }
+
+    // 单位后只跟一位数字时按下一级单位计，如 一百五 = 150、两万五 = 25000
+    let tail = match pending {
+        Some(p) if digits_after_unit == 1 && last_unit > 1e1 => p * last_unit / 10.0,
+        Some(p) => p,
+        None => 0.0,
+    };
+
+    Some((total + section + tail, len))
+}
+
+// 递归下降求值：expr = term (('+' | '-') term)*，term = factor (('*' | '/') factor)*
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).copied();
+        self.pos += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn expr(&mut self) -> Result<f64, String> {
+        let mut value = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.factor()?;
+        while let Some(Token::Op(op @ ('*' | '/'))) = self.peek() {
+            self.pos += 1;
+            let rhs = self.factor()?;
+            if op == '/' && rhs == 0.0 {
+                return Err("除数不能为 0".to_string());
+            }
+            value = if op == '*' { value * rhs } else { value / rhs };
+        }
+        Ok(value)
+    }
+
+    fn factor(&mut self) -> Result<f64, String> {
+        match self.next() {
+            Some(Token::Num(value)) => Ok(value),
+            Some(Token::Op('-')) => Ok(-self.factor()?),
+            Some(Token::Op('+')) => self.factor(),
+            Some(Token::LParen) => {
+                let value = self.expr()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(value),
+                    _ => Err("括号不匹配".to_string()),
+                }
+            }
+            _ => Err("表达式不完整".to_string()),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::expr::{evaluate, expression_end};
+
+    #[test]
+    fn test_evaluate() {
+        assert_eq!(evaluate("100"), Ok(100.0));
+        assert_eq!(evaluate("1200/12"), Ok(100.0));
+        assert_eq!(evaluate("3x 49.99"), Ok(149.97));
+        assert_eq!(evaluate("2 × 3 ÷ 4"), Ok(1.5));
+        assert!((evaluate("(120+35.5)*1.08").unwrap() - 167.94).abs() < 1e-9);
+        assert_eq!(evaluate("-(2+3)*2"), Ok(-10.0));
+
+        // 数量级后缀
+        assert_eq!(evaluate("2.5k"), Ok(2500.0));
+        assert_eq!(evaluate("1.2bn"), Ok(1.2e9));
+        assert_eq!(evaluate("5万"), Ok(50000.0));
+        assert_eq!(evaluate("3亿"), Ok(3e8));
+        assert_eq!(evaluate("2千万"), Ok(2e7));
+        assert_eq!(evaluate("3m*2"), Ok(6e6));
+
+        // 中文数字
+        assert_eq!(evaluate("一百五十"), Ok(150.0));
+        assert_eq!(evaluate("三千二百"), Ok(3200.0));
+        assert_eq!(evaluate("两万五"), Ok(25000.0));
+        assert_eq!(evaluate("壹佰伍拾"), Ok(150.0));
+        assert_eq!(evaluate("十五"), Ok(15.0));
+        assert_eq!(evaluate("一百零五"), Ok(105.0));
+        assert_eq!(evaluate("一亿两千万"), Ok(1.2e8));
+        assert_eq!(evaluate("三点五"), Ok(3.5));
+        assert_eq!(evaluate("一万零二十"), Ok(10020.0));
+        assert!(evaluate("1/0").is_err());
+        assert!(evaluate("").is_err());
+    }
+
+    #[test]
+    fn test_expression_end() {
+        // 表达式之后的货币代码不计入
+        assert_eq!(expression_end("(120+35.5)*1.08 usd cny"), 15);
+        assert_eq!(expression_end("14.1usd"), 4);
+        assert_eq!(expression_end("3x 49.99 eur"), 8);
+        assert_eq!(expression_end("3 xaf"), 1);
+        assert_eq!(expression_end("3m jpy"), 2);
+        assert_eq!(expression_end("3mxn"), 1);
+        assert_eq!(expression_end("5万日元"), 4);
+        assert_eq!(expression_end("一百五十 美元"), 12);
+        assert_eq!(expression_end("两万五人民币"), 9);
+        assert_eq!(expression_end("百慕大元"), 0);
+        // 不完整的结尾回退到最后一个完整表达式
+        assert_eq!(expression_end("100 + usd"), 3);
+        assert_eq!(expression_end("(100 usd"), 0);
+        assert_eq!(expression_end("usd 100"), 0);
+    }
+}
@@ -1,9 +1,13 @@
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 use serde::Serialize;
-use crate::model::CurrencyInfo;
-use crate::PRIORITY;
-use serde_json::Value;
-use crate::matcher::match_currencies;
+use crate::config::Config;
+use crate::model::{Amount, CurrencyInfo};
+use crate::query::{CurrencyRef, ParseError, Term};
+use crate::matcher::{ambiguous_matches, match_currencies, sort_currencies};
+use crate::units::price_factor;
+use crate::usage::Usage;
 
 const ICON_PATH: &str = "images/flags";
 #[derive(Serialize)]
@@ -13,7 +17,7 @@ struct AlfredOutput {
 
 // 为AlfredOutput添加序列化方法
 impl AlfredOutput {
-    fn to_json(self) -> String {
+    fn to_json(&self) -> String {
         serde_json::to_string(&self).unwrap_or_else(|_| "{\"items\":[]}".to_string())
     }
 }
@@ -26,6 +30,19 @@ struct AlfredItem {
     autocomplete: Option<String>,
     icon: Icon,
     valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<Text>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quicklookurl: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variables: Option<HashMap<String, String>>, // 传给后续动作，用于记录使用的货币对
+}
+
+// 复制（⌘C）和大字显示（⌘L）的文本
+#[derive(Serialize)]
+struct Text {
+    copy: String,
+    largetype: String,
 }
 
 #[derive(Serialize)]
@@ -35,20 +52,27 @@ struct Icon {
 
 pub fn show_error(message: &str) -> String {
     let output = AlfredOutput {
-        items: vec![AlfredItem {
-            title: message.to_string(),
-            subtitle: "".to_string(),
-            arg: None,
-            autocomplete: None,
-            icon: Icon {
-                path: "".to_string(),
-            },
-            valid: false,
-        }],
+        items: vec![create_error_item(message)],
     };
     serde_json::to_string(&output).unwrap()
 }
 
+fn create_error_item(message: &str) -> AlfredItem {
+    AlfredItem {
+        title: message.to_string(),
+        subtitle: "".to_string(),
+        arg: None,
+        autocomplete: None,
+        icon: Icon {
+            path: "".to_string(),
+        },
+        valid: false,
+        text: None,
+        quicklookurl: None,
+        variables: None,
+    }
+}
+
 pub fn show_instructions() -> String {
     let output = AlfredOutput {
         items: vec![AlfredItem {
@@ -60,51 +84,43 @@ pub fn show_instructions() -> String {
                 path: "".to_string(),
             },
             valid: false,
+            text: None,
+            quicklookurl: None,
+            variables: None,
         }],
     };
     serde_json::to_string(&output).unwrap()
 }
 
 
-pub fn show_all_currencies(amount: f64, currencies: &HashMap<String, CurrencyInfo>) -> String {
-    let mut items = Vec::new();
-    // 先添加优先货币
-    for code in &PRIORITY {
-        if let Some(info) = currencies.get(*code) {
-            items.push(create_currency_item(
-                amount,
-                code,
-                info,
-                true, // 需要自动补全
-            ));
-        }
-    }
+pub fn show_all_currencies(
+    amount: &Amount,
+    currencies: &HashMap<String, CurrencyInfo>,
+    config: &Config,
+    usage: &Usage,
+) -> String {
+    // 常用货币在前
+    let mut sorted: Vec<_> = currencies.iter().collect();
+    sort_currencies(&mut sorted, None, config, usage);
 
-    // 添加其他货币（排除已添加的优先货币）
-    for (code, info) in currencies {
-        if !PRIORITY.contains(&code.as_str()) {
-            items.push(create_currency_item(
-                amount,
-                code,
-                info,
-                true,
-            ));
-        }
-    }
+    let items = sorted
+        .into_iter()
+        .map(|(code, info)| create_currency_item(amount, code, info, true))
+        .collect();
 
     AlfredOutput { items }.to_json()
 }
 
 // 辅助函数：创建货币展示项
 fn create_currency_item(
-    amount: f64,
+    amount: &Amount,
     code: &str,
     info: &CurrencyInfo,
     with_autocomplete: bool,
 ) -> AlfredItem {
     AlfredItem {
-        title: format!("{} {}", amount, code),
-        subtitle: format!("{} {}", info.country, info.coin),
+        title: format!("{} {}", amount.value, code),
+        subtitle: format!("{}{}", amount.echo(), info.display_name()),
         arg: None,
         autocomplete: if with_autocomplete {
             Some(format!("{} {} to ", amount, code))
@@ -115,19 +131,24 @@ fn create_currency_item(
             path: format!("{}/{}.png", ICON_PATH, code),
         },
         valid: false,
+        text: None,
+        quicklookurl: None,
+        variables: None,
     }
 }
 
 
 pub fn show_source_currencies(
-    amount: f64,
-    src: &str,
+    amount: &Amount,
+    src: &CurrencyRef,
     currencies: &HashMap<String, CurrencyInfo>,
+    config: &Config,
+    usage: &Usage,
 ) -> String {
-    let matches = match_currencies(src, currencies);
+    let matches = match_currencies(&src.text, currencies, config, usage);
 
     if matches.is_empty() {
-        return show_error("未找到匹配的货币");
+        return show_error(&ParseError::missing_currency(src, currencies).to_string());
     }
 
     if matches.len() > 1 {
@@ -139,64 +160,205 @@ pub fn show_source_currencies(
         return AlfredOutput { items }.to_json();
     }
 
-    // 单个匹配时显示目标货币选择（带优先级）
+    // 单个匹配时显示目标货币选择，常用的组合在前
     let (src_code, src_info) = matches[0];
-    let mut items = Vec::new();
+    let mut targets: Vec<_> = currencies.iter().filter(|(code, _)| *code != src_code).collect();
+    sort_currencies(&mut targets, Some(src_code), config, usage);
 
-    // 先添加优先货币
-    for code in &PRIORITY {
-        if let Some(info) = currencies.get(*code) {
-            if *code != src_code {
-                items.push(create_conversion_item(amount, src_code, src_info, code, info));
-            }
-        }
-    }
+    let items = targets
+        .into_iter()
+        .map(|(code, info)| create_conversion_item(amount, src_code, src_info, code, info))
+        .collect();
+
+    AlfredOutput { items }.to_json()
+}
 
-    // 添加其他货币
-    for (code, info) in currencies {
-        if !PRIORITY.contains(&code.as_str()) && code != src_code {
-            items.push(create_conversion_item(amount, src_code, src_info, code, info));
+// 名称对应多种货币时（如 比索、dollar、元）列出候选货币，标题带国家/地区，选中后把输入中的名称替换为代码
+// 按输入顺序找第一个有歧义的货币词，没有时返回 None
+pub fn show_choices(
+    input: &str,
+    refs: &[&CurrencyRef],
+    currencies: &HashMap<String, CurrencyInfo>,
+    config: &Config,
+    usage: &Usage,
+) -> Option<String> {
+    refs.iter().find_map(|currency| {
+        let matches = ambiguous_matches(&currency.text, currencies, config, usage);
+        if matches.is_empty() {
+            return None;
         }
-    }
+        let items = matches
+            .into_iter()
+            .map(|(code, info)| {
+                let completed = replace_currency(input, currency, code);
+                AlfredItem {
+                    title: format!("{} {}", info.display_name(), code),
+                    subtitle: format!("「{}」对应多种货币，选择后为：{}", currency.text, completed),
+                    arg: None,
+                    autocomplete: Some(completed),
+                    icon: Icon {
+                        path: format!("{}/{}.png", ICON_PATH, code),
+                    },
+                    valid: false,
+                    text: None,
+                    quicklookurl: None,
+                    variables: None,
+                }
+            })
+            .collect();
+        Some(AlfredOutput { items }.to_json())
+    })
+}
 
-    AlfredOutput { items }.to_json()
+// 把输入中的货币词替换为代码；合并的英文名称在原输入中可能隔着多个空白
+fn replace_currency(input: &str, currency: &CurrencyRef, code: &str) -> String {
+    let start = input.char_indices().nth(currency.pos - 1).map_or(input.len(), |(i, _)| i);
+    let mut rest = &input[start..];
+    for c in currency.text.chars() {
+        rest = match c {
+            ' ' => rest.trim_start(),
+            _ => rest.get(rest.chars().next().map_or(0, char::len_utf8)..).unwrap_or_default(),
+        };
+    }
+    format!("{}{}{}", &input[..start], code, rest)
 }
 
 pub fn convert_currency(
-    amount: f64,
-    src: &str,
-    dst: &str,
+    amount: &Amount,
+    src: &CurrencyRef,
+    dsts: &[CurrencyRef],
     currencies: &HashMap<String, CurrencyInfo>,
+    config: &Config,
+    usage: &Usage,
 ) -> String {
-    let src_matches = match_currencies(src, currencies);
-    let dst_matches = match_currencies(dst, currencies);
+    let src_matches = match_currencies(&src.text, currencies, config, usage);
+    if src_matches.is_empty() {
+        return show_error(&ParseError::missing_currency(src, currencies).to_string());
+    }
+    // 有歧义的名称已先由 show_choices 选择，这里的第一个匹配即为所选
+    let (src_code, src_info) = src_matches[0];
 
-    if src_matches.is_empty() || dst_matches.is_empty() {
-        return show_error("无效的货币代码");
+    // 单个目标时列出所有匹配的货币，多个目标时按输入顺序每个目标一行
+    let items: Vec<_> = match dsts {
+        [dst] => {
+            let dst_matches = match_currencies(&dst.text, currencies, config, usage);
+            if dst_matches.is_empty() {
+                return show_error(&ParseError::missing_currency(dst, currencies).to_string());
+            }
+            dst_matches
+                .into_iter()
+                .filter(|(code, _)| *code != src_code)
+                .map(|(dst_code, dst_info)| create_conversion_item(amount, src_code, src_info, dst_code, dst_info))
+                .collect()
+        }
+        _ => dsts
+            .iter()
+            .map(|dst| match match_currencies(&dst.text, currencies, config, usage).first() {
+                Some((dst_code, dst_info)) => create_conversion_item(amount, src_code, src_info, dst_code, dst_info),
+                None => create_error_item(&ParseError::missing_currency(dst, currencies).to_string()),
+            })
+            .collect(),
+    };
+
+    if items.is_empty() {
+        show_error("不能转换相同货币")
+    } else {
+        AlfredOutput { items }.to_json()
     }
+}
 
-    let (src_code, src_info) = &src_matches[0];
-    let items: Vec<_> = dst_matches
-        .into_iter()
-        .filter(|(code, _)| *code != *src_code)
-        .map(|(dst_code, dst_info)| {
-            let converted = (amount * dst_info.rate / src_info.rate * 100.0).round() / 100.0;
+
+// 单价换算：货币按汇率换算，单位按计量单位换算，如 3.45 usd/gal → cny/l
+pub fn convert_price(
+    amount: &Amount,
+    src: &CurrencyRef,
+    dsts: &[CurrencyRef],
+    currencies: &HashMap<String, CurrencyInfo>,
+    config: &Config,
+    usage: &Usage,
+) -> String {
+    let (Some(&(src_code, src_info)), Some(src_unit)) = (match_currencies(&src.text, currencies, config, usage).first(), src.unit) else {
+        return show_error(&ParseError::missing_currency(src, currencies).to_string());
+    };
+
+    let items = dsts
+        .iter()
+        .map(|dst| {
+            let (Some(&(dst_code, dst_info)), Some(dst_unit)) = (match_currencies(&dst.text, currencies, config, usage).first(), dst.unit) else {
+                return create_error_item(&ParseError::missing_currency(dst, currencies).to_string());
+            };
+            let price = amount.value * dst_info.rate / src_info.rate * price_factor(src_unit, dst_unit, config);
+            let price = if price >= 1.0 { (price * 100.0).round() / 100.0 } else { round_rate(price) };
+            // 单位不同时附上换算关系，时间换算依赖工时时注明假设
+            let scale = src_unit.factor(config) / dst_unit.factor(config);
+            let mut ratio = match src_unit == dst_unit {
+                true => String::new(),
+                // 较大的单位在前，如 1 年 = 12 月
+                false if scale >= 1.0 => format!(" · 1 {} = {} {}", src_unit.symbol, round_rate(scale), dst_unit.symbol),
+                false => format!(" · 1 {} = {} {}", dst_unit.symbol, round_rate(1.0 / scale), src_unit.symbol),
+            };
+            if src_unit.depends_on_work_time(dst_unit) {
+                ratio.push_str(&format!(
+                    "（按每天 {} 小时、每周 {} 天、每年 52 周计）",
+                    config.hours_per_day, config.days_per_week
+                ));
+            }
             AlfredItem {
-                title: format!("{} {}", converted, dst_code),
+                title: format!("{} {}/{}", price, dst_code, dst_unit.symbol),
                 subtitle: format!(
-                    "{} {} → {} {}",
-                    src_info.country, src_info.coin, dst_info.country, dst_info.coin
+                    "{}{} {}/{} → {}{}{}{}",
+                    amount.echo(),
+                    amount.value,
+                    src_code,
+                    src_unit.symbol,
+                    dst_info.display_name(),
+                    ratio,
+                    retirement_note(src_code, src_info),
+                    retirement_note(dst_code, dst_info)
                 ),
-                arg: Some(converted.to_string()),
-                autocomplete: Some(format!("{} {} {}", amount, src_code, dst_code)),
+                arg: Some(price.to_string()),
+                autocomplete: Some(format!("{} {}/{} {}/{}", amount, src_code, src_unit.names[0], dst_code, dst_unit.names[0])),
                 icon: Icon {
                     path: format!("{}/{}.png", ICON_PATH, dst_code),
                 },
                 valid: true,
+                text: None,
+                quicklookurl: None,
+                variables: pair_variables(src_code, dst_code),
             }
         })
         .collect();
 
+    AlfredOutput { items }.to_json()
+}
+
+// 未输入金额的货币对：每个目标货币显示汇率和反向汇率
+pub fn show_rates(
+    src: &CurrencyRef,
+    dsts: &[CurrencyRef],
+    currencies: &HashMap<String, CurrencyInfo>,
+    config: &Config,
+    usage: &Usage,
+) -> String {
+    let Some(&(src_code, src_info)) = match_currencies(&src.text, currencies, config, usage).first() else {
+        return show_error(&ParseError::missing_currency(src, currencies).to_string());
+    };
+
+    let mut items = Vec::new();
+    for dst in dsts {
+        let dst_matches = match_currencies(&dst.text, currencies, config, usage);
+        if dst_matches.is_empty() {
+            items.push(create_error_item(&ParseError::missing_currency(dst, currencies).to_string()));
+            continue;
+        }
+        // 只匹配到源货币时跳过，全部跳过时提示不能转换相同货币
+        let Some(&(dst_code, dst_info)) = dst_matches.iter().find(|(code, _)| *code != src_code) else {
+            continue;
+        };
+        items.push(create_rate_item(src_code, src_info, dst_code, dst_info));
+        items.push(create_rate_item(dst_code, dst_info, src_code, src_info));
+    }
+
     if items.is_empty() {
         show_error("不能转换相同货币")
     } else {
@@ -204,20 +366,204 @@ pub fn convert_currency(
     }
 }
 
+fn create_rate_item(
+    src_code: &str,
+    src_info: &CurrencyInfo,
+    dst_code: &str,
+    dst_info: &CurrencyInfo,
+) -> AlfredItem {
+    let rate = round_rate(dst_info.rate / src_info.rate);
+    AlfredItem {
+        title: format!("1 {} = {} {}", src_code, rate, dst_code),
+        subtitle: format!(
+            "{} → {}{}{}",
+            src_info.display_name(),
+            dst_info.display_name(),
+            retirement_note(src_code, src_info),
+            retirement_note(dst_code, dst_info)
+        ),
+        arg: Some(rate.to_string()),
+        autocomplete: Some(format!("{} {}", src_code, dst_code)),
+        icon: Icon {
+            path: format!("{}/{}.png", ICON_PATH, dst_code),
+        },
+        valid: true,
+        text: None,
+        quicklookurl: None,
+        variables: pair_variables(src_code, dst_code),
+    }
+}
+
+// 汇率保留 4 位小数，小于 1 时保留 4 位有效数字，如 0.0006849
+fn round_rate(rate: f64) -> f64 {
+    let digits = if rate >= 1.0 { 4 } else { 3 - rate.log10().floor() as i32 };
+    let scale = 10f64.powi(digits);
+    (rate * scale).round() / scale
+}
+
+// 对照表：每档金额一行，目标货币并列显示；完整表格用于大字显示，
+// 给出 quicklook_path 时另写入该文件供快速查看
+pub fn show_table(
+    amounts: &[f64],
+    src: &CurrencyRef,
+    dsts: &[CurrencyRef],
+    currencies: &HashMap<String, CurrencyInfo>,
+    quicklook_path: Option<&Path>,
+    config: &Config,
+    usage: &Usage,
+) -> String {
+    let Some(&(src_code, src_info)) = match_currencies(&src.text, currencies, config, usage).first() else {
+        return show_error(&ParseError::missing_currency(src, currencies).to_string());
+    };
+
+    let mut targets = Vec::new();
+    for dst in dsts {
+        match match_currencies(&dst.text, currencies, config, usage).iter().find(|(code, _)| *code != src_code) {
+            Some(&(dst_code, dst_info)) => targets.push((dst_code, dst_info)),
+            None => return show_error(&ParseError::missing_currency(dst, currencies).to_string()),
+        }
+    }
+    let Some(&(first_code, first_info)) = targets.first() else {
+        return show_error("请输入目标货币，如 100..1000 usd cny step 100");
+    };
+
+    // 每行的换算结果，金额右对齐
+    let width = amounts.iter().map(|a| a.to_string().len()).max().unwrap_or(0);
+    let rows: Vec<(f64, f64, String)> = amounts
+        .iter()
+        .map(|&amount| {
+            let values: Vec<_> = targets
+                .iter()
+                .map(|(dst_code, dst_info)| {
+                    let value = (amount * dst_info.rate / src_info.rate * 100.0).round() / 100.0;
+                    (value, format!("{} {}", value, dst_code))
+                })
+                .collect();
+            let converted = values.iter().map(|(_, text)| text.as_str()).collect::<Vec<_>>().join(" = ");
+            let line = format!("{:>width$} {} = {}", amount, src_code, converted, width = width);
+            (amount, values[0].0, line)
+        })
+        .collect();
+
+    let targets_label = targets.iter().map(|(code, _)| code.as_str()).collect::<Vec<_>>().join(" / ");
+    let table = format!(
+        "{} → {} 对照表\n{}",
+        src_code,
+        targets_label,
+        rows.iter().map(|(_, _, line)| line.as_str()).collect::<Vec<_>>().join("\n")
+    );
+    // Alfred 要求 quicklookurl 为绝对路径
+    let quicklookurl = quicklook_path
+        .filter(|path| fs::write(path, &table).is_ok())
+        .and_then(|path| fs::canonicalize(path).ok())
+        .map(|path| path.to_string_lossy().to_string());
+
+    let items = rows
+        .iter()
+        .map(|(amount, value, line)| AlfredItem {
+            title: line.trim_start().to_string(),
+            subtitle: format!("{} → {} · ⌘L 查看对照表", src_info.display_name(), first_info.display_name()),
+            arg: Some(value.to_string()),
+            autocomplete: Some(format!("{} {} {}", amount, src_code, first_code)),
+            icon: Icon {
+                path: format!("{}/{}.png", ICON_PATH, first_code),
+            },
+            valid: true,
+            text: Some(Text {
+                copy: line.trim_start().to_string(),
+                largetype: table.clone(),
+            }),
+            quicklookurl: quicklookurl.clone(),
+            variables: pair_variables(src_code, first_code),
+        })
+        .collect();
+
+    AlfredOutput { items }.to_json()
+}
+
+// 多币种求和：先显示每个目标货币的合计，再逐项显示换算到第一个目标货币的明细
+pub fn convert_sum(
+    terms: &[Term],
+    dsts: &[CurrencyRef],
+    currencies: &HashMap<String, CurrencyInfo>,
+    config: &Config,
+    usage: &Usage,
+) -> String {
+    let mut sources = Vec::new();
+    for term in terms {
+        let (Some(amount), Some(src)) = (&term.amount, &term.source) else { continue };
+        match match_currencies(&src.text, currencies, config, usage).first() {
+            Some(&(src_code, src_info)) => sources.push((amount, src_code, src_info)),
+            None => return show_error(&ParseError::missing_currency(src, currencies).to_string()),
+        }
+    }
+
+    let mut targets = Vec::new();
+    for dst in dsts {
+        match match_currencies(&dst.text, currencies, config, usage).first() {
+            Some(&(dst_code, dst_info)) => targets.push((dst_code, dst_info)),
+            None => return show_error(&ParseError::missing_currency(dst, currencies).to_string()),
+        }
+    }
+
+    let labels: Vec<_> = sources
+        .iter()
+        .map(|(amount, src_code, _)| format!("{} {}", amount.value, src_code))
+        .collect();
+    let mut items: Vec<_> = targets
+        .iter()
+        .map(|(dst_code, dst_info)| {
+            let total: f64 = sources
+                .iter()
+                .map(|(amount, _, src_info)| amount.value * dst_info.rate / src_info.rate)
+                .sum();
+            let total = (total * 100.0).round() / 100.0;
+            AlfredItem {
+                title: format!("合计 {} {}", total, dst_code),
+                subtitle: labels.join(" + "),
+                arg: Some(total.to_string()),
+                autocomplete: None,
+                icon: Icon {
+                    path: format!("{}/{}.png", ICON_PATH, dst_code),
+                },
+                valid: true,
+                text: None,
+                quicklookurl: None,
+                variables: None,
+            }
+        })
+        .collect();
+
+    if let Some(&(dst_code, dst_info)) = targets.first() {
+        for &(amount, src_code, src_info) in &sources {
+            let mut item = create_conversion_item(amount, src_code, src_info, dst_code, dst_info);
+            item.title = format!("{} {} = {}", amount.value, src_code, item.title);
+            item.autocomplete = None;
+            items.push(item);
+        }
+    }
+
+    AlfredOutput { items }.to_json()
+}
+
 
 fn create_conversion_item(
-    amount: f64,
+    amount: &Amount,
     src_code: &str,
     src_info: &CurrencyInfo,
     dst_code: &str,
     dst_info: &CurrencyInfo,
 ) -> AlfredItem {
-    let converted = (amount * dst_info.rate / src_info.rate * 100.0).round() / 100.0;
+    let converted = (amount.value * dst_info.rate / src_info.rate * 100.0).round() / 100.0;
     AlfredItem {
         title: format!("{} {}", converted, dst_code),
         subtitle: format!(
-            "{} {} → {} {}",
-            src_info.country, src_info.coin, dst_info.country, dst_info.coin
+            "{}{} → {}{}{}",
+            amount.echo(),
+            src_info.display_name(),
+            dst_info.display_name(),
+            retirement_note(src_code, src_info),
+            retirement_note(dst_code, dst_info)
         ),
         arg: Some(converted.to_string()),
         autocomplete: Some(format!("{} {} {}", amount, src_code, dst_code)),
@@ -225,5 +571,26 @@ fn create_conversion_item(
             path: format!("{}/{}.png", ICON_PATH, dst_code),
         },
         valid: true,
+        text: None,
+        quicklookurl: None,
+        variables: pair_variables(src_code, dst_code),
     }
 }
+
+// 已停用货币的说明，如 " · HRK 已于 2023-01-01 由 EUR 取代，按 1 EUR = 7.5345 HRK 换算"
+fn retirement_note(code: &str, info: &CurrencyInfo) -> String {
+    info.retirement.map_or(String::new(), |r| {
+        format!(
+            " · {} 已于 {} 由 {} 取代，按 1 {} = {} {} 换算",
+            code, r.since, r.successor, r.successor, r.per_successor, code
+        )
+    })
+}
+
+// 选中换算结果时传给后续动作的变量，由 --record 记录使用的货币对
+fn pair_variables(src_code: &str, dst_code: &str) -> Option<HashMap<String, String>> {
+    Some(HashMap::from([
+        ("source".to_string(), src_code.to_string()),
+        ("target".to_string(), dst_code.to_string()),
+    ]))
+}
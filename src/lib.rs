@@ -1,10 +1,35 @@
 pub mod model;
+pub mod config;
 pub mod api;
+pub mod expr;
+pub mod provider;
+pub mod query;
 pub mod parser;
 pub mod matcher;
 pub mod formatter;
+pub mod units;
+pub mod usage;
 
-const PRIORITY: [&str; 8] = ["CNY", "USD", "BHD", "EUR", "AED", "HKD", "GBP", "JPY"]; // 优先货币列表
+use config::Language;
+use model::Retirement;
+
+const PRIORITY: [&str; 7] = ["CNY", "USD", "EUR", "HKD", "GBP", "JPY", "AED"]; // 没有使用记录时的优先货币
+
+// 已停用或重新定值的货币，按固定比率由继任货币换算
+static RETIRED_CURRENCIES: &[Retirement] = &[
+    Retirement { code: "HRK", successor: "EUR", per_successor: 7.5345, since: "2023-01-01" },
+    Retirement { code: "SLL", successor: "SLE", per_successor: 1000.0, since: "2022-07-01" },
+    // 2018 年 100000:1 换成 VES，2021 年 VES 再去掉 6 个零（代码不变）
+    Retirement { code: "VEF", successor: "VES", per_successor: 1e11, since: "2018-08-20" },
+    Retirement { code: "ZWL", successor: "ZWG", per_successor: 2498.7242, since: "2024-04-05" },
+    Retirement { code: "ANG", successor: "XCG", per_successor: 1.0, since: "2025-03-31" },
+    Retirement { code: "BGN", successor: "EUR", per_successor: 1.95583, since: "2026-01-01" },
+];
+
+// 货币的停用信息，仍在使用时为 None
+fn retirement(code: &str) -> Option<&'static Retirement> {
+    RETIRED_CURRENCIES.iter().find(|r| r.code == code)
+}
 
 static CURRENCY_NAMES_CN: &[(&str, (&str, &str))] = &[
     ("AED", ("阿联酋", "迪拉姆")),
@@ -156,17 +181,917 @@ static CURRENCY_NAMES_CN: &[(&str, (&str, &str))] = &[
     ("USD", ("美国", "美元")),
     ("UYU", ("乌拉圭", "比索")),
     ("UZS", ("乌兹别克斯坦", "苏姆")),
+    ("VEF", ("委内瑞拉", "强势玻利瓦尔")),
     ("VES", ("委内瑞拉", "玻利瓦尔")),
     ("VND", ("越南", "盾")),
     ("VUV", ("瓦努阿图", "瓦图")),
     ("WST", ("萨摩亚", "塔拉")),
     ("XAF", ("中非金融共同体", "法郎")),
     ("XCD", ("东加勒比", "元")),
+    ("XCG", ("库拉索和荷属圣马丁", "加勒比盾")),
     ("XDR", ("国际货币基金组织", "特别提款权")),
     ("XOF", ("西非金融共同体", "法郎")),
     ("XPF", ("太平洋法郎", "法郎")),
     ("YER", ("也门", "里亚尔")),
     ("ZAR", ("南非", "兰特")),
     ("ZMW", ("赞比亚", "克瓦查")),
-    ("ZWL", ("津巴布韦", "元"))
-];
\ No newline at end of file
+    ("ZWG", ("津巴布韦", "金")),
+    ("ZWL", ("津巴布韦", "元")),
+];
+// 英文国家/地区名和货币名
+static CURRENCY_NAMES_EN: &[(&str, (&str, &str))] = &[
+    ("AED", ("United Arab Emirates", "UAE Dirham")),
+    ("AFN", ("Afghanistan", "Afghan Afghani")),
+    ("ALL", ("Albania", "Albanian Lek")),
+    ("AMD", ("Armenia", "Armenian Dram")),
+    ("ANG", ("Netherlands Antilles", "Antillean Guilder")),
+    ("AOA", ("Angola", "Angolan Kwanza")),
+    ("ARS", ("Argentina", "Argentine Peso")),
+    ("AUD", ("Australia", "Australian Dollar")),
+    ("AWG", ("Aruba", "Aruban Florin")),
+    ("AZN", ("Azerbaijan", "Azerbaijani Manat")),
+    ("BAM", ("Bosnia and Herzegovina", "Convertible Mark")),
+    ("BBD", ("Barbados", "Barbadian Dollar")),
+    ("BDT", ("Bangladesh", "Bangladeshi Taka")),
+    ("BGN", ("Bulgaria", "Bulgarian Lev")),
+    ("BHD", ("Bahrain", "Bahraini Dinar")),
+    ("BIF", ("Burundi", "Burundian Franc")),
+    ("BMD", ("Bermuda", "Bermudian Dollar")),
+    ("BND", ("Brunei", "Brunei Dollar")),
+    ("BOB", ("Bolivia", "Bolivian Boliviano")),
+    ("BRL", ("Brazil", "Brazilian Real")),
+    ("BSD", ("Bahamas", "Bahamian Dollar")),
+    ("BTN", ("Bhutan", "Bhutanese Ngultrum")),
+    ("BWP", ("Botswana", "Botswana Pula")),
+    ("BYN", ("Belarus", "Belarusian Ruble")),
+    ("BZD", ("Belize", "Belize Dollar")),
+    ("CAD", ("Canada", "Canadian Dollar")),
+    ("CDF", ("DR Congo", "Congolese Franc")),
+    ("CHF", ("Switzerland", "Swiss Franc")),
+    ("CLP", ("Chile", "Chilean Peso")),
+    ("CNY", ("China", "Chinese Yuan")),
+    ("COP", ("Colombia", "Colombian Peso")),
+    ("CRC", ("Costa Rica", "Costa Rican Colon")),
+    ("CUP", ("Cuba", "Cuban Peso")),
+    ("CVE", ("Cape Verde", "Cape Verdean Escudo")),
+    ("CZK", ("Czech Republic", "Czech Koruna")),
+    ("DJF", ("Djibouti", "Djiboutian Franc")),
+    ("DKK", ("Denmark", "Danish Krone")),
+    ("DOP", ("Dominican Republic", "Dominican Peso")),
+    ("DZD", ("Algeria", "Algerian Dinar")),
+    ("EGP", ("Egypt", "Egyptian Pound")),
+    ("ERN", ("Eritrea", "Eritrean Nakfa")),
+    ("ETB", ("Ethiopia", "Ethiopian Birr")),
+    ("EUR", ("European Union", "Euro")),
+    ("FJD", ("Fiji", "Fijian Dollar")),
+    ("FKP", ("Falkland Islands", "Falkland Islands Pound")),
+    ("FOK", ("Faroe Islands", "Faroese Krona")),
+    ("GBP", ("United Kingdom", "British Pound")),
+    ("GEL", ("Georgia", "Georgian Lari")),
+    ("GGP", ("Guernsey", "Guernsey Pound")),
+    ("GHS", ("Ghana", "Ghanaian Cedi")),
+    ("GIP", ("Gibraltar", "Gibraltar Pound")),
+    ("GMD", ("Gambia", "Gambian Dalasi")),
+    ("GNF", ("Guinea", "Guinean Franc")),
+    ("GTQ", ("Guatemala", "Guatemalan Quetzal")),
+    ("GYD", ("Guyana", "Guyanese Dollar")),
+    ("HKD", ("Hong Kong", "Hong Kong Dollar")),
+    ("HNL", ("Honduras", "Honduran Lempira")),
+    ("HRK", ("Croatia", "Croatian Kuna")),
+    ("HTG", ("Haiti", "Haitian Gourde")),
+    ("HUF", ("Hungary", "Hungarian Forint")),
+    ("IDR", ("Indonesia", "Indonesian Rupiah")),
+    ("ILS", ("Israel", "Israeli New Shekel")),
+    ("IMP", ("Isle of Man", "Manx Pound")),
+    ("INR", ("India", "Indian Rupee")),
+    ("IQD", ("Iraq", "Iraqi Dinar")),
+    ("IRR", ("Iran", "Iranian Rial")),
+    ("ISK", ("Iceland", "Icelandic Krona")),
+    ("JEP", ("Jersey", "Jersey Pound")),
+    ("JMD", ("Jamaica", "Jamaican Dollar")),
+    ("JOD", ("Jordan", "Jordanian Dinar")),
+    ("JPY", ("Japan", "Japanese Yen")),
+    ("KES", ("Kenya", "Kenyan Shilling")),
+    ("KGS", ("Kyrgyzstan", "Kyrgyzstani Som")),
+    ("KHR", ("Cambodia", "Cambodian Riel")),
+    ("KID", ("Kiribati", "Kiribati Dollar")),
+    ("KMF", ("Comoros", "Comorian Franc")),
+    ("KRW", ("South Korea", "South Korean Won")),
+    ("KWD", ("Kuwait", "Kuwaiti Dinar")),
+    ("KYD", ("Cayman Islands", "Cayman Islands Dollar")),
+    ("KZT", ("Kazakhstan", "Kazakhstani Tenge")),
+    ("LAK", ("Laos", "Lao Kip")),
+    ("LBP", ("Lebanon", "Lebanese Pound")),
+    ("LKR", ("Sri Lanka", "Sri Lankan Rupee")),
+    ("LRD", ("Liberia", "Liberian Dollar")),
+    ("LSL", ("Lesotho", "Lesotho Loti")),
+    ("LYD", ("Libya", "Libyan Dinar")),
+    ("MAD", ("Morocco", "Moroccan Dirham")),
+    ("MDL", ("Moldova", "Moldovan Leu")),
+    ("MGA", ("Madagascar", "Malagasy Ariary")),
+    ("MKD", ("North Macedonia", "Macedonian Denar")),
+    ("MMK", ("Myanmar", "Myanmar Kyat")),
+    ("MNT", ("Mongolia", "Mongolian Tugrik")),
+    ("MOP", ("Macau", "Macanese Pataca")),
+    ("MRU", ("Mauritania", "Mauritanian Ouguiya")),
+    ("MUR", ("Mauritius", "Mauritian Rupee")),
+    ("MVR", ("Maldives", "Maldivian Rufiyaa")),
+    ("MWK", ("Malawi", "Malawian Kwacha")),
+    ("MXN", ("Mexico", "Mexican Peso")),
+    ("MYR", ("Malaysia", "Malaysian Ringgit")),
+    ("MZN", ("Mozambique", "Mozambican Metical")),
+    ("NAD", ("Namibia", "Namibian Dollar")),
+    ("NGN", ("Nigeria", "Nigerian Naira")),
+    ("NIO", ("Nicaragua", "Nicaraguan Cordoba")),
+    ("NOK", ("Norway", "Norwegian Krone")),
+    ("NPR", ("Nepal", "Nepalese Rupee")),
+    ("NZD", ("New Zealand", "New Zealand Dollar")),
+    ("OMR", ("Oman", "Omani Rial")),
+    ("PAB", ("Panama", "Panamanian Balboa")),
+    ("PEN", ("Peru", "Peruvian Sol")),
+    ("PGK", ("Papua New Guinea", "Papua New Guinean Kina")),
+    ("PHP", ("Philippines", "Philippine Peso")),
+    ("PKR", ("Pakistan", "Pakistani Rupee")),
+    ("PLN", ("Poland", "Polish Zloty")),
+    ("PYG", ("Paraguay", "Paraguayan Guarani")),
+    ("QAR", ("Qatar", "Qatari Riyal")),
+    ("RON", ("Romania", "Romanian Leu")),
+    ("RSD", ("Serbia", "Serbian Dinar")),
+    ("RUB", ("Russia", "Russian Ruble")),
+    ("RWF", ("Rwanda", "Rwandan Franc")),
+    ("SAR", ("Saudi Arabia", "Saudi Riyal")),
+    ("SBD", ("Solomon Islands", "Solomon Islands Dollar")),
+    ("SCR", ("Seychelles", "Seychellois Rupee")),
+    ("SDG", ("Sudan", "Sudanese Pound")),
+    ("SEK", ("Sweden", "Swedish Krona")),
+    ("SGD", ("Singapore", "Singapore Dollar")),
+    ("SHP", ("Saint Helena", "Saint Helena Pound")),
+    ("SLE", ("Sierra Leone", "Sierra Leonean Leone")),
+    ("SLL", ("Sierra Leone", "Sierra Leonean Leone (old)")),
+    ("SOS", ("Somalia", "Somali Shilling")),
+    ("SRD", ("Suriname", "Surinamese Dollar")),
+    ("SSP", ("South Sudan", "South Sudanese Pound")),
+    ("STN", ("Sao Tome and Principe", "Sao Tome and Principe Dobra")),
+    ("SYP", ("Syria", "Syrian Pound")),
+    ("SZL", ("Eswatini", "Swazi Lilangeni")),
+    ("THB", ("Thailand", "Thai Baht")),
+    ("TJS", ("Tajikistan", "Tajikistani Somoni")),
+    ("TMT", ("Turkmenistan", "Turkmenistan Manat")),
+    ("TND", ("Tunisia", "Tunisian Dinar")),
+    ("TOP", ("Tonga", "Tongan Paanga")),
+    ("TRY", ("Turkey", "Turkish Lira")),
+    ("TTD", ("Trinidad and Tobago", "Trinidad and Tobago Dollar")),
+    ("TVD", ("Tuvalu", "Tuvaluan Dollar")),
+    ("TWD", ("Taiwan", "New Taiwan Dollar")),
+    ("TZS", ("Tanzania", "Tanzanian Shilling")),
+    ("UAH", ("Ukraine", "Ukrainian Hryvnia")),
+    ("UGX", ("Uganda", "Ugandan Shilling")),
+    ("USD", ("United States", "US Dollar")),
+    ("UYU", ("Uruguay", "Uruguayan Peso")),
+    ("UZS", ("Uzbekistan", "Uzbekistani Som")),
+    ("VEF", ("Venezuela", "Venezuelan Bolivar Fuerte")),
+    ("VES", ("Venezuela", "Venezuelan Bolivar")),
+    ("VND", ("Vietnam", "Vietnamese Dong")),
+    ("VUV", ("Vanuatu", "Vanuatu Vatu")),
+    ("WST", ("Samoa", "Samoan Tala")),
+    ("XAF", ("Central African CFA", "Central African CFA Franc")),
+    ("XCD", ("Eastern Caribbean", "East Caribbean Dollar")),
+    ("XCG", ("Curacao and Sint Maarten", "Caribbean Guilder")),
+    ("XDR", ("International Monetary Fund", "Special Drawing Rights")),
+    ("XOF", ("West African CFA", "West African CFA Franc")),
+    ("XPF", ("French Pacific", "CFP Franc")),
+    ("YER", ("Yemen", "Yemeni Rial")),
+    ("ZAR", ("South Africa", "South African Rand")),
+    ("ZMW", ("Zambia", "Zambian Kwacha")),
+    ("ZWG", ("Zimbabwe", "Zimbabwe Gold")),
+    ("ZWL", ("Zimbabwe", "Zimbabwean Dollar")),
+];
+
+// 繁体中文名称
+static CURRENCY_NAMES_TW: &[(&str, (&str, &str))] = &[
+    ("AED", ("阿聯酋", "迪拉姆")),
+    ("AFN", ("阿富汗", "阿富汗尼")),
+    ("ALL", ("阿爾巴尼亞", "列克")),
+    ("AMD", ("亞美尼亞", "德拉姆")),
+    ("ANG", ("荷屬安地列斯", "盾")),
+    ("AOA", ("安哥拉", "寬扎")),
+    ("ARS", ("阿根廷", "披索")),
+    ("AUD", ("澳洲", "澳幣")),
+    ("AWG", ("阿魯巴", "弗羅林")),
+    ("AZN", ("亞塞拜然", "馬納特")),
+    ("BAM", ("波士尼亞與赫塞哥維納", "可兌換馬克")),
+    ("BBD", ("巴貝多", "元")),
+    ("BDT", ("孟加拉", "塔卡")),
+    ("BGN", ("保加利亞", "列弗")),
+    ("BHD", ("巴林", "第納爾")),
+    ("BIF", ("蒲隆地", "法郎")),
+    ("BMD", ("百慕達", "元")),
+    ("BND", ("汶萊", "元")),
+    ("BOB", ("玻利維亞", "玻利維亞諾")),
+    ("BRL", ("巴西", "雷亞爾")),
+    ("BSD", ("巴哈馬", "元")),
+    ("BTN", ("不丹", "努爾特魯姆")),
+    ("BWP", ("波札那", "普拉")),
+    ("BYN", ("白俄羅斯", "盧布")),
+    ("BZD", ("貝里斯", "元")),
+    ("CAD", ("加拿大", "加幣")),
+    ("CDF", ("剛果民主共和國", "法郎")),
+    ("CHF", ("瑞士", "法郎")),
+    ("CLP", ("智利", "披索")),
+    ("CNY", ("中國", "人民幣")),
+    ("COP", ("哥倫比亞", "披索")),
+    ("CRC", ("哥斯大黎加", "科朗")),
+    ("CUP", ("古巴", "披索")),
+    ("CVE", ("維德角", "埃斯庫多")),
+    ("CZK", ("捷克", "克朗")),
+    ("DJF", ("吉布地", "法郎")),
+    ("DKK", ("丹麥", "克朗")),
+    ("DOP", ("多明尼加", "披索")),
+    ("DZD", ("阿爾及利亞", "第納爾")),
+    ("EGP", ("埃及", "鎊")),
+    ("ERN", ("厄利垂亞", "納克法")),
+    ("ETB", ("衣索比亞", "比爾")),
+    ("EUR", ("歐盟", "歐元")),
+    ("FJD", ("斐濟", "元")),
+    ("FKP", ("福克蘭群島", "鎊")),
+    ("FOK", ("法羅群島", "克朗")),
+    ("GBP", ("英國", "英鎊")),
+    ("GEL", ("喬治亞", "拉里")),
+    ("GGP", ("根西島", "鎊")),
+    ("GHS", ("迦納", "塞地")),
+    ("GIP", ("直布羅陀", "鎊")),
+    ("GMD", ("甘比亞", "達拉西")),
+    ("GNF", ("幾內亞", "法郎")),
+    ("GTQ", ("瓜地馬拉", "格查爾")),
+    ("GYD", ("蓋亞那", "元")),
+    ("HKD", ("香港", "港幣")),
+    ("HNL", ("宏都拉斯", "倫皮拉")),
+    ("HRK", ("克羅埃西亞", "庫納")),
+    ("HTG", ("海地", "古德")),
+    ("HUF", ("匈牙利", "福林")),
+    ("IDR", ("印尼", "盾")),
+    ("ILS", ("以色列", "新謝克爾")),
+    ("IMP", ("曼島", "鎊")),
+    ("INR", ("印度", "盧比")),
+    ("IQD", ("伊拉克", "第納爾")),
+    ("IRR", ("伊朗", "里亞爾")),
+    ("ISK", ("冰島", "克朗")),
+    ("JEP", ("澤西島", "鎊")),
+    ("JMD", ("牙買加", "元")),
+    ("JOD", ("約旦", "第納爾")),
+    ("JPY", ("日本", "日圓")),
+    ("KES", ("肯亞", "先令")),
+    ("KGS", ("吉爾吉斯", "索姆")),
+    ("KHR", ("柬埔寨", "瑞爾")),
+    ("KID", ("吉里巴斯", "元")),
+    ("KMF", ("葛摩", "法郎")),
+    ("KRW", ("韓國", "韓元")),
+    ("KWD", ("科威特", "第納爾")),
+    ("KYD", ("開曼群島", "元")),
+    ("KZT", ("哈薩克", "堅戈")),
+    ("LAK", ("寮國", "基普")),
+    ("LBP", ("黎巴嫩", "鎊")),
+    ("LKR", ("斯里蘭卡", "盧比")),
+    ("LRD", ("賴比瑞亞", "元")),
+    ("LSL", ("賴索托", "洛蒂")),
+    ("LYD", ("利比亞", "第納爾")),
+    ("MAD", ("摩洛哥", "迪拉姆")),
+    ("MDL", ("摩爾多瓦", "列伊")),
+    ("MGA", ("馬達加斯加", "阿里亞里")),
+    ("MKD", ("北馬其頓", "代納爾")),
+    ("MMK", ("緬甸", "緬元")),
+    ("MNT", ("蒙古", "圖格里克")),
+    ("MOP", ("澳門", "澳門幣")),
+    ("MRU", ("茅利塔尼亞", "烏吉亞")),
+    ("MUR", ("模里西斯", "盧比")),
+    ("MVR", ("馬爾地夫", "拉菲亞")),
+    ("MWK", ("馬拉威", "克瓦查")),
+    ("MXN", ("墨西哥", "披索")),
+    ("MYR", ("馬來西亞", "令吉")),
+    ("MZN", ("莫三比克", "梅蒂卡爾")),
+    ("NAD", ("納米比亞", "元")),
+    ("NGN", ("奈及利亞", "奈拉")),
+    ("NIO", ("尼加拉瓜", "科多巴")),
+    ("NOK", ("挪威", "克朗")),
+    ("NPR", ("尼泊爾", "盧比")),
+    ("NZD", ("紐西蘭", "紐幣")),
+    ("OMR", ("阿曼", "里亞爾")),
+    ("PAB", ("巴拿馬", "巴波亞")),
+    ("PEN", ("秘魯", "索爾")),
+    ("PGK", ("巴布亞紐幾內亞", "基那")),
+    ("PHP", ("菲律賓", "披索")),
+    ("PKR", ("巴基斯坦", "盧比")),
+    ("PLN", ("波蘭", "茲羅提")),
+    ("PYG", ("巴拉圭", "瓜拉尼")),
+    ("QAR", ("卡達", "里亞爾")),
+    ("RON", ("羅馬尼亞", "列伊")),
+    ("RSD", ("塞爾維亞", "第納爾")),
+    ("RUB", ("俄羅斯", "盧布")),
+    ("RWF", ("盧安達", "法郎")),
+    ("SAR", ("沙烏地阿拉伯", "里亞爾")),
+    ("SBD", ("索羅門群島", "元")),
+    ("SCR", ("塞席爾", "盧比")),
+    ("SDG", ("蘇丹", "鎊")),
+    ("SEK", ("瑞典", "克朗")),
+    ("SGD", ("新加坡", "新加坡幣")),
+    ("SHP", ("聖赫勒拿", "鎊")),
+    ("SLE", ("獅子山", "新利昂")),
+    ("SLL", ("獅子山", "利昂")),
+    ("SOS", ("索馬利亞", "先令")),
+    ("SRD", ("蘇利南", "元")),
+    ("SSP", ("南蘇丹", "鎊")),
+    ("STN", ("聖多美普林西比", "多布拉")),
+    ("SYP", ("敘利亞", "鎊")),
+    ("SZL", ("史瓦帝尼", "里蘭吉尼")),
+    ("THB", ("泰國", "泰銖")),
+    ("TJS", ("塔吉克", "索莫尼")),
+    ("TMT", ("土庫曼", "馬納特")),
+    ("TND", ("突尼西亞", "第納爾")),
+    ("TOP", ("東加", "潘加")),
+    ("TRY", ("土耳其", "里拉")),
+    ("TTD", ("千里達及托巴哥", "元")),
+    ("TVD", ("吐瓦魯", "元")),
+    ("TWD", ("台灣", "新台幣")),
+    ("TZS", ("坦尚尼亞", "先令")),
+    ("UAH", ("烏克蘭", "格里夫納")),
+    ("UGX", ("烏干達", "先令")),
+    ("USD", ("美國", "美元")),
+    ("UYU", ("烏拉圭", "披索")),
+    ("UZS", ("烏茲別克", "蘇姆")),
+    ("VEF", ("委內瑞拉", "強勢玻利瓦")),
+    ("VES", ("委內瑞拉", "玻利瓦")),
+    ("VND", ("越南", "越南盾")),
+    ("VUV", ("萬那杜", "瓦圖")),
+    ("WST", ("薩摩亞", "塔拉")),
+    ("XAF", ("中非金融共同體", "法郎")),
+    ("XCD", ("東加勒比", "元")),
+    ("XCG", ("庫拉索和荷屬聖馬丁", "加勒比盾")),
+    ("XDR", ("國際貨幣基金組織", "特別提款權")),
+    ("XOF", ("西非金融共同體", "法郎")),
+    ("XPF", ("太平洋法郎", "法郎")),
+    ("YER", ("葉門", "里亞爾")),
+    ("ZAR", ("南非", "蘭特")),
+    ("ZMW", ("尚比亞", "克瓦查")),
+    ("ZWG", ("辛巴威", "金")),
+    ("ZWL", ("辛巴威", "元")),
+];
+
+// 日文名称
+static CURRENCY_NAMES_JA: &[(&str, (&str, &str))] = &[
+    ("AED", ("アラブ首長国連邦", "UAEディルハム")),
+    ("AFN", ("アフガニスタン", "アフガニ")),
+    ("ALL", ("アルバニア", "レク")),
+    ("AMD", ("アルメニア", "ドラム")),
+    ("ANG", ("オランダ領アンティル", "アンティル・ギルダー")),
+    ("AOA", ("アンゴラ", "クワンザ")),
+    ("ARS", ("アルゼンチン", "アルゼンチン・ペソ")),
+    ("AUD", ("オーストラリア", "オーストラリア・ドル")),
+    ("AWG", ("アルバ", "アルバ・フロリン")),
+    ("AZN", ("アゼルバイジャン", "アゼルバイジャン・マナト")),
+    ("BAM", ("ボスニア・ヘルツェゴビナ", "兌換マルク")),
+    ("BBD", ("バルバドス", "バルバドス・ドル")),
+    ("BDT", ("バングラデシュ", "タカ")),
+    ("BGN", ("ブルガリア", "レフ")),
+    ("BHD", ("バーレーン", "バーレーン・ディナール")),
+    ("BIF", ("ブルンジ", "ブルンジ・フラン")),
+    ("BMD", ("バミューダ", "バミューダ・ドル")),
+    ("BND", ("ブルネイ", "ブルネイ・ドル")),
+    ("BOB", ("ボリビア", "ボリビアーノ")),
+    ("BRL", ("ブラジル", "レアル")),
+    ("BSD", ("バハマ", "バハマ・ドル")),
+    ("BTN", ("ブータン", "ニュルタム")),
+    ("BWP", ("ボツワナ", "プラ")),
+    ("BYN", ("ベラルーシ", "ベラルーシ・ルーブル")),
+    ("BZD", ("ベリーズ", "ベリーズ・ドル")),
+    ("CAD", ("カナダ", "カナダ・ドル")),
+    ("CDF", ("コンゴ民主共和国", "コンゴ・フラン")),
+    ("CHF", ("スイス", "スイス・フラン")),
+    ("CLP", ("チリ", "チリ・ペソ")),
+    ("CNY", ("中国", "人民元")),
+    ("COP", ("コロンビア", "コロンビア・ペソ")),
+    ("CRC", ("コスタリカ", "コスタリカ・コロン")),
+    ("CUP", ("キューバ", "キューバ・ペソ")),
+    ("CVE", ("カーボベルデ", "カーボベルデ・エスクード")),
+    ("CZK", ("チェコ", "チェコ・コルナ")),
+    ("DJF", ("ジブチ", "ジブチ・フラン")),
+    ("DKK", ("デンマーク", "デンマーク・クローネ")),
+    ("DOP", ("ドミニカ共和国", "ドミニカ・ペソ")),
+    ("DZD", ("アルジェリア", "アルジェリア・ディナール")),
+    ("EGP", ("エジプト", "エジプト・ポンド")),
+    ("ERN", ("エリトリア", "ナクファ")),
+    ("ETB", ("エチオピア", "ブル")),
+    ("EUR", ("欧州連合", "ユーロ")),
+    ("FJD", ("フィジー", "フィジー・ドル")),
+    ("FKP", ("フォークランド諸島", "フォークランド諸島ポンド")),
+    ("FOK", ("フェロー諸島", "フェロー・クローナ")),
+    ("GBP", ("イギリス", "英ポンド")),
+    ("GEL", ("ジョージア", "ラリ")),
+    ("GGP", ("ガーンジー", "ガーンジー・ポンド")),
+    ("GHS", ("ガーナ", "セディ")),
+    ("GIP", ("ジブラルタル", "ジブラルタル・ポンド")),
+    ("GMD", ("ガンビア", "ダラシ")),
+    ("GNF", ("ギニア", "ギニア・フラン")),
+    ("GTQ", ("グアテマラ", "ケツァル")),
+    ("GYD", ("ガイアナ", "ガイアナ・ドル")),
+    ("HKD", ("香港", "香港ドル")),
+    ("HNL", ("ホンジュラス", "レンピラ")),
+    ("HRK", ("クロアチア", "クーナ")),
+    ("HTG", ("ハイチ", "グールド")),
+    ("HUF", ("ハンガリー", "フォリント")),
+    ("IDR", ("インドネシア", "ルピア")),
+    ("ILS", ("イスラエル", "新シェケル")),
+    ("IMP", ("マン島", "マン島ポンド")),
+    ("INR", ("インド", "インド・ルピー")),
+    ("IQD", ("イラク", "イラク・ディナール")),
+    ("IRR", ("イラン", "イラン・リアル")),
+    ("ISK", ("アイスランド", "アイスランド・クローナ")),
+    ("JEP", ("ジャージー", "ジャージー・ポンド")),
+    ("JMD", ("ジャマイカ", "ジャマイカ・ドル")),
+    ("JOD", ("ヨルダン", "ヨルダン・ディナール")),
+    ("JPY", ("日本", "円")),
+    ("KES", ("ケニア", "ケニア・シリング")),
+    ("KGS", ("キルギス", "ソム")),
+    ("KHR", ("カンボジア", "リエル")),
+    ("KID", ("キリバス", "キリバス・ドル")),
+    ("KMF", ("コモロ", "コモロ・フラン")),
+    ("KRW", ("韓国", "ウォン")),
+    ("KWD", ("クウェート", "クウェート・ディナール")),
+    ("KYD", ("ケイマン諸島", "ケイマン諸島ドル")),
+    ("KZT", ("カザフスタン", "テンゲ")),
+    ("LAK", ("ラオス", "キープ")),
+    ("LBP", ("レバノン", "レバノン・ポンド")),
+    ("LKR", ("スリランカ", "スリランカ・ルピー")),
+    ("LRD", ("リベリア", "リベリア・ドル")),
+    ("LSL", ("レソト", "ロチ")),
+    ("LYD", ("リビア", "リビア・ディナール")),
+    ("MAD", ("モロッコ", "モロッコ・ディルハム")),
+    ("MDL", ("モルドバ", "モルドバ・レウ")),
+    ("MGA", ("マダガスカル", "アリアリ")),
+    ("MKD", ("北マケドニア", "デナール")),
+    ("MMK", ("ミャンマー", "チャット")),
+    ("MNT", ("モンゴル", "トゥグルグ")),
+    ("MOP", ("マカオ", "パタカ")),
+    ("MRU", ("モーリタニア", "ウギア")),
+    ("MUR", ("モーリシャス", "モーリシャス・ルピー")),
+    ("MVR", ("モルディブ", "ルフィヤ")),
+    ("MWK", ("マラウイ", "マラウイ・クワチャ")),
+    ("MXN", ("メキシコ", "メキシコ・ペソ")),
+    ("MYR", ("マレーシア", "リンギット")),
+    ("MZN", ("モザンビーク", "メティカル")),
+    ("NAD", ("ナミビア", "ナミビア・ドル")),
+    ("NGN", ("ナイジェリア", "ナイラ")),
+    ("NIO", ("ニカラグア", "コルドバ")),
+    ("NOK", ("ノルウェー", "ノルウェー・クローネ")),
+    ("NPR", ("ネパール", "ネパール・ルピー")),
+    ("NZD", ("ニュージーランド", "ニュージーランド・ドル")),
+    ("OMR", ("オマーン", "オマーン・リアル")),
+    ("PAB", ("パナマ", "バルボア")),
+    ("PEN", ("ペルー", "ソル")),
+    ("PGK", ("パプアニューギニア", "キナ")),
+    ("PHP", ("フィリピン", "フィリピン・ペソ")),
+    ("PKR", ("パキスタン", "パキスタン・ルピー")),
+    ("PLN", ("ポーランド", "ズウォティ")),
+    ("PYG", ("パラグアイ", "グアラニー")),
+    ("QAR", ("カタール", "カタール・リヤル")),
+    ("RON", ("ルーマニア", "ルーマニア・レウ")),
+    ("RSD", ("セルビア", "セルビア・ディナール")),
+    ("RUB", ("ロシア", "ロシア・ルーブル")),
+    ("RWF", ("ルワンダ", "ルワンダ・フラン")),
+    ("SAR", ("サウジアラビア", "サウジアラビア・リヤル")),
+    ("SBD", ("ソロモン諸島", "ソロモン諸島ドル")),
+    ("SCR", ("セーシェル", "セーシェル・ルピー")),
+    ("SDG", ("スーダン", "スーダン・ポンド")),
+    ("SEK", ("スウェーデン", "スウェーデン・クローナ")),
+    ("SGD", ("シンガポール", "シンガポール・ドル")),
+    ("SHP", ("セントヘレナ", "セントヘレナ・ポンド")),
+    ("SLE", ("シエラレオネ", "新レオン")),
+    ("SLL", ("シエラレオネ", "レオン")),
+    ("SOS", ("ソマリア", "ソマリア・シリング")),
+    ("SRD", ("スリナム", "スリナム・ドル")),
+    ("SSP", ("南スーダン", "南スーダン・ポンド")),
+    ("STN", ("サントメ・プリンシペ", "ドブラ")),
+    ("SYP", ("シリア", "シリア・ポンド")),
+    ("SZL", ("エスワティニ", "リランゲニ")),
+    ("THB", ("タイ", "バーツ")),
+    ("TJS", ("タジキスタン", "ソモニ")),
+    ("TMT", ("トルクメニスタン", "トルクメニスタン・マナト")),
+    ("TND", ("チュニジア", "チュニジア・ディナール")),
+    ("TOP", ("トンガ", "パアンガ")),
+    ("TRY", ("トルコ", "トルコ・リラ")),
+    ("TTD", ("トリニダード・トバゴ", "トリニダード・トバゴ・ドル")),
+    ("TVD", ("ツバル", "ツバル・ドル")),
+    ("TWD", ("台湾", "ニュー台湾ドル")),
+    ("TZS", ("タンザニア", "タンザニア・シリング")),
+    ("UAH", ("ウクライナ", "フリヴニャ")),
+    ("UGX", ("ウガンダ", "ウガンダ・シリング")),
+    ("USD", ("アメリカ", "米ドル")),
+    ("UYU", ("ウルグアイ", "ウルグアイ・ペソ")),
+    ("UZS", ("ウズベキスタン", "スム")),
+    ("VEF", ("ベネズエラ", "ボリバル・フエルテ")),
+    ("VES", ("ベネズエラ", "ボリバル")),
+    ("VND", ("ベトナム", "ドン")),
+    ("VUV", ("バヌアツ", "バツ")),
+    ("WST", ("サモア", "タラ")),
+    ("XAF", ("中部アフリカ諸国", "CFAフラン（中部アフリカ）")),
+    ("XCD", ("東カリブ", "東カリブ・ドル")),
+    ("XCG", ("キュラソー・シント・マールテン", "カリブ・ギルダー")),
+    ("XDR", ("国際通貨基金", "特別引出権")),
+    ("XOF", ("西アフリカ諸国", "CFAフラン（西アフリカ）")),
+    ("XPF", ("フランス領ポリネシア", "CFPフラン")),
+    ("YER", ("イエメン", "イエメン・リアル")),
+    ("ZAR", ("南アフリカ", "ランド")),
+    ("ZMW", ("ザンビア", "ザンビア・クワチャ")),
+    ("ZWG", ("ジンバブエ", "ジンバブエ・ゴールド")),
+    ("ZWL", ("ジンバブエ", "ジンバブエ・ドル")),
+];
+
+// 指定语言的国家/地区名和货币名，缺少时用简体中文
+fn currency_names(code: &str, language: Language) -> Option<(&'static str, &'static str)> {
+    let table = match language {
+        Language::ZhHans => CURRENCY_NAMES_CN,
+        Language::ZhHant => CURRENCY_NAMES_TW,
+        Language::En => CURRENCY_NAMES_EN,
+        Language::Ja => CURRENCY_NAMES_JA,
+    };
+    [table, CURRENCY_NAMES_CN]
+        .iter()
+        .find_map(|table| table.iter().find(|(c, _)| *c == code))
+        .map(|(_, names)| *names)
+}
+
+// 所有语言的名称，用于搜索
+fn all_currency_names(code: &str) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+    [CURRENCY_NAMES_CN, CURRENCY_NAMES_TW, CURRENCY_NAMES_EN, CURRENCY_NAMES_JA]
+        .into_iter()
+        .filter_map(move |table| table.iter().find(|(c, _)| *c == code))
+        .map(|(_, names)| *names)
+}
+
+// 货币别名（小写）：拼音、口语和英文俗称，可通过 currency_aliases 配置补充
+static CURRENCY_ALIASES: &[(&str, &str)] = &[
+    // 拼音
+    ("rmb", "CNY"),
+    ("renminbi", "CNY"),
+    ("yuan", "CNY"),
+    ("kuai", "CNY"),
+    ("meiyuan", "USD"),
+    ("meijin", "USD"),
+    ("meidao", "USD"),
+    ("riyuan", "JPY"),
+    ("ouyuan", "EUR"),
+    ("gangbi", "HKD"),
+    ("gangyuan", "HKD"),
+    ("yingbang", "GBP"),
+    ("hanyuan", "KRW"),
+    ("taibi", "TWD"),
+    ("aomenbi", "MOP"),
+    ("aoyuan", "AUD"),
+    ("jiayuan", "CAD"),
+    ("xinbi", "SGD"),
+    ("ruilang", "CHF"),
+    ("lubu", "RUB"),
+    ("taizhu", "THB"),
+    // 中文口语
+    ("刀", "USD"),
+    ("美刀", "USD"),
+    ("美金", "USD"),
+    ("块", "CNY"),
+    ("元", "CNY"),
+    ("软妹币", "CNY"),
+    ("港纸", "HKD"),
+    ("瑞郎", "CHF"),
+    // 英文俗称
+    ("buck", "USD"),
+    ("bucks", "USD"),
+    ("greenback", "USD"),
+    ("quid", "GBP"),
+    ("sterling", "GBP"),
+    ("pound", "GBP"),
+    ("euro", "EUR"),
+    ("euros", "EUR"),
+    ("yen", "JPY"),
+    ("won", "KRW"),
+    ("loonie", "CAD"),
+    ("aussie", "AUD"),
+    ("kiwi", "NZD"),
+    ("swissie", "CHF"),
+    ("rupee", "INR"),
+    ("ruble", "RUB"),
+    ("rouble", "RUB"),
+    ("baht", "THB"),
+    ("lira", "TRY"),
+    ("rand", "ZAR"),
+    // 国家/地区俗称
+    ("uk", "GBP"),
+    ("england", "GBP"),
+    ("usa", "USD"),
+    ("america", "USD"),
+    ("holland", "EUR"),
+];
+
+// 国家/地区（ISO 3166 两位代码、英文名、中文名）使用的货币，包括欧元区、使用美元的国家和各属地
+// 同时使用多种货币的按本国货币计，如巴拿马为 PAB
+static COUNTRIES: &[(&str, &str, &str, &str)] = &[
+    ("AD", "Andorra", "安道尔", "EUR"),
+    ("AE", "United Arab Emirates", "阿联酋", "AED"),
+    ("AF", "Afghanistan", "阿富汗", "AFN"),
+    ("AG", "Antigua and Barbuda", "安提瓜和巴布达", "XCD"),
+    ("AI", "Anguilla", "安圭拉", "XCD"),
+    ("AL", "Albania", "阿尔巴尼亚", "ALL"),
+    ("AM", "Armenia", "亚美尼亚", "AMD"),
+    ("AO", "Angola", "安哥拉", "AOA"),
+    ("AR", "Argentina", "阿根廷", "ARS"),
+    ("AS", "American Samoa", "美属萨摩亚", "USD"),
+    ("AT", "Austria", "奥地利", "EUR"),
+    ("AU", "Australia", "澳大利亚", "AUD"),
+    ("AW", "Aruba", "阿鲁巴", "AWG"),
+    ("AX", "Aland Islands", "奥兰群岛", "EUR"),
+    ("AZ", "Azerbaijan", "阿塞拜疆", "AZN"),
+    ("BA", "Bosnia and Herzegovina", "波黑", "BAM"),
+    ("BB", "Barbados", "巴巴多斯", "BBD"),
+    ("BD", "Bangladesh", "孟加拉国", "BDT"),
+    ("BE", "Belgium", "比利时", "EUR"),
+    ("BF", "Burkina Faso", "布基纳法索", "XOF"),
+    ("BG", "Bulgaria", "保加利亚", "EUR"),
+    ("BH", "Bahrain", "巴林", "BHD"),
+    ("BI", "Burundi", "布隆迪", "BIF"),
+    ("BJ", "Benin", "贝宁", "XOF"),
+    ("BL", "Saint Barthelemy", "圣巴泰勒米", "EUR"),
+    ("BM", "Bermuda", "百慕大", "BMD"),
+    ("BN", "Brunei", "文莱", "BND"),
+    ("BO", "Bolivia", "玻利维亚", "BOB"),
+    ("BQ", "Caribbean Netherlands", "荷兰加勒比区", "USD"),
+    ("BR", "Brazil", "巴西", "BRL"),
+    ("BS", "Bahamas", "巴哈马", "BSD"),
+    ("BT", "Bhutan", "不丹", "BTN"),
+    ("BW", "Botswana", "博茨瓦纳", "BWP"),
+    ("BY", "Belarus", "白俄罗斯", "BYN"),
+    ("BZ", "Belize", "伯利兹", "BZD"),
+    ("CA", "Canada", "加拿大", "CAD"),
+    ("CC", "Cocos Islands", "科科斯群岛", "AUD"),
+    ("CD", "DR Congo", "刚果（金）", "CDF"),
+    ("CF", "Central African Republic", "中非", "XAF"),
+    ("CG", "Republic of the Congo", "刚果（布）", "XAF"),
+    ("CH", "Switzerland", "瑞士", "CHF"),
+    ("CI", "Ivory Coast", "科特迪瓦", "XOF"),
+    ("CK", "Cook Islands", "库克群岛", "NZD"),
+    ("CL", "Chile", "智利", "CLP"),
+    ("CM", "Cameroon", "喀麦隆", "XAF"),
+    ("CN", "China", "中国", "CNY"),
+    ("CO", "Colombia", "哥伦比亚", "COP"),
+    ("CR", "Costa Rica", "哥斯达黎加", "CRC"),
+    ("CU", "Cuba", "古巴", "CUP"),
+    ("CV", "Cape Verde", "佛得角", "CVE"),
+    ("CW", "Curacao", "库拉索", "XCG"),
+    ("CX", "Christmas Island", "圣诞岛", "AUD"),
+    ("CY", "Cyprus", "塞浦路斯", "EUR"),
+    ("CZ", "Czechia", "捷克", "CZK"),
+    ("DE", "Germany", "德国", "EUR"),
+    ("DJ", "Djibouti", "吉布提", "DJF"),
+    ("DK", "Denmark", "丹麦", "DKK"),
+    ("DM", "Dominica", "多米尼克", "XCD"),
+    ("DO", "Dominican Republic", "多米尼加", "DOP"),
+    ("DZ", "Algeria", "阿尔及利亚", "DZD"),
+    ("EC", "Ecuador", "厄瓜多尔", "USD"),
+    ("EE", "Estonia", "爱沙尼亚", "EUR"),
+    ("EG", "Egypt", "埃及", "EGP"),
+    ("EH", "Western Sahara", "西撒哈拉", "MAD"),
+    ("ER", "Eritrea", "厄立特里亚", "ERN"),
+    ("ES", "Spain", "西班牙", "EUR"),
+    ("ET", "Ethiopia", "埃塞俄比亚", "ETB"),
+    ("FI", "Finland", "芬兰", "EUR"),
+    ("FJ", "Fiji", "斐济", "FJD"),
+    ("FK", "Falkland Islands", "福克兰群岛", "FKP"),
+    ("FM", "Micronesia", "密克罗尼西亚", "USD"),
+    ("FO", "Faroe Islands", "法罗群岛", "FOK"),
+    ("FR", "France", "法国", "EUR"),
+    ("GA", "Gabon", "加蓬", "XAF"),
+    ("GB", "United Kingdom", "英国", "GBP"),
+    ("GD", "Grenada", "格林纳达", "XCD"),
+    ("GE", "Georgia", "格鲁吉亚", "GEL"),
+    ("GF", "French Guiana", "法属圭亚那", "EUR"),
+    ("GG", "Guernsey", "根西岛", "GGP"),
+    ("GH", "Ghana", "加纳", "GHS"),
+    ("GI", "Gibraltar", "直布罗陀", "GIP"),
+    ("GL", "Greenland", "格陵兰", "DKK"),
+    ("GM", "Gambia", "冈比亚", "GMD"),
+    ("GN", "Guinea", "几内亚", "GNF"),
+    ("GP", "Guadeloupe", "瓜德罗普", "EUR"),
+    ("GQ", "Equatorial Guinea", "赤道几内亚", "XAF"),
+    ("GR", "Greece", "希腊", "EUR"),
+    ("GT", "Guatemala", "危地马拉", "GTQ"),
+    ("GU", "Guam", "关岛", "USD"),
+    ("GW", "Guinea-Bissau", "几内亚比绍", "XOF"),
+    ("GY", "Guyana", "圭亚那", "GYD"),
+    ("HK", "Hong Kong", "香港", "HKD"),
+    ("HN", "Honduras", "洪都拉斯", "HNL"),
+    ("HR", "Croatia", "克罗地亚", "EUR"),
+    ("HT", "Haiti", "海地", "HTG"),
+    ("HU", "Hungary", "匈牙利", "HUF"),
+    ("ID", "Indonesia", "印度尼西亚", "IDR"),
+    ("IE", "Ireland", "爱尔兰", "EUR"),
+    ("IL", "Israel", "以色列", "ILS"),
+    ("IM", "Isle of Man", "马恩岛", "IMP"),
+    ("IN", "India", "印度", "INR"),
+    ("IO", "British Indian Ocean Territory", "英属印度洋领地", "USD"),
+    ("IQ", "Iraq", "伊拉克", "IQD"),
+    ("IR", "Iran", "伊朗", "IRR"),
+    ("IS", "Iceland", "冰岛", "ISK"),
+    ("IT", "Italy", "意大利", "EUR"),
+    ("JE", "Jersey", "泽西岛", "JEP"),
+    ("JM", "Jamaica", "牙买加", "JMD"),
+    ("JO", "Jordan", "约旦", "JOD"),
+    ("JP", "Japan", "日本", "JPY"),
+    ("KE", "Kenya", "肯尼亚", "KES"),
+    ("KG", "Kyrgyzstan", "吉尔吉斯斯坦", "KGS"),
+    ("KH", "Cambodia", "柬埔寨", "KHR"),
+    ("KI", "Kiribati", "基里巴斯", "AUD"),
+    ("KM", "Comoros", "科摩罗", "KMF"),
+    ("KN", "Saint Kitts and Nevis", "圣基茨和尼维斯", "XCD"),
+    ("KR", "South Korea", "韩国", "KRW"),
+    ("KW", "Kuwait", "科威特", "KWD"),
+    ("KY", "Cayman Islands", "开曼群岛", "KYD"),
+    ("KZ", "Kazakhstan", "哈萨克斯坦", "KZT"),
+    ("LA", "Laos", "老挝", "LAK"),
+    ("LB", "Lebanon", "黎巴嫩", "LBP"),
+    ("LC", "Saint Lucia", "圣卢西亚", "XCD"),
+    ("LI", "Liechtenstein", "列支敦士登", "CHF"),
+    ("LK", "Sri Lanka", "斯里兰卡", "LKR"),
+    ("LR", "Liberia", "利比里亚", "LRD"),
+    ("LS", "Lesotho", "莱索托", "LSL"),
+    ("LT", "Lithuania", "立陶宛", "EUR"),
+    ("LU", "Luxembourg", "卢森堡", "EUR"),
+    ("LV", "Latvia", "拉脱维亚", "EUR"),
+    ("LY", "Libya", "利比亚", "LYD"),
+    ("MA", "Morocco", "摩洛哥", "MAD"),
+    ("MC", "Monaco", "摩纳哥", "EUR"),
+    ("MD", "Moldova", "摩尔多瓦", "MDL"),
+    ("ME", "Montenegro", "黑山", "EUR"),
+    ("MF", "Saint Martin", "法属圣马丁", "EUR"),
+    ("MG", "Madagascar", "马达加斯加", "MGA"),
+    ("MH", "Marshall Islands", "马绍尔群岛", "USD"),
+    ("MK", "North Macedonia", "北马其顿", "MKD"),
+    ("ML", "Mali", "马里", "XOF"),
+    ("MM", "Myanmar", "缅甸", "MMK"),
+    ("MN", "Mongolia", "蒙古", "MNT"),
+    ("MO", "Macau", "澳门", "MOP"),
+    ("MP", "Northern Mariana Islands", "北马里亚纳群岛", "USD"),
+    ("MQ", "Martinique", "马提尼克", "EUR"),
+    ("MR", "Mauritania", "毛里塔尼亚", "MRU"),
+    ("MS", "Montserrat", "蒙特塞拉特", "XCD"),
+    ("MT", "Malta", "马耳他", "EUR"),
+    ("MU", "Mauritius", "毛里求斯", "MUR"),
+    ("MV", "Maldives", "马尔代夫", "MVR"),
+    ("MW", "Malawi", "马拉维", "MWK"),
+    ("MX", "Mexico", "墨西哥", "MXN"),
+    ("MY", "Malaysia", "马来西亚", "MYR"),
+    ("MZ", "Mozambique", "莫桑比克", "MZN"),
+    ("NA", "Namibia", "纳米比亚", "NAD"),
+    ("NC", "New Caledonia", "新喀里多尼亚", "XPF"),
+    ("NE", "Niger", "尼日尔", "XOF"),
+    ("NF", "Norfolk Island", "诺福克岛", "AUD"),
+    ("NG", "Nigeria", "尼日利亚", "NGN"),
+    ("NI", "Nicaragua", "尼加拉瓜", "NIO"),
+    ("NL", "Netherlands", "荷兰", "EUR"),
+    ("NO", "Norway", "挪威", "NOK"),
+    ("NP", "Nepal", "尼泊尔", "NPR"),
+    ("NR", "Nauru", "瑙鲁", "AUD"),
+    ("NU", "Niue", "纽埃", "NZD"),
+    ("NZ", "New Zealand", "新西兰", "NZD"),
+    ("OM", "Oman", "阿曼", "OMR"),
+    ("PA", "Panama", "巴拿马", "PAB"),
+    ("PE", "Peru", "秘鲁", "PEN"),
+    ("PF", "French Polynesia", "法属波利尼西亚", "XPF"),
+    ("PG", "Papua New Guinea", "巴布亚新几内亚", "PGK"),
+    ("PH", "Philippines", "菲律宾", "PHP"),
+    ("PK", "Pakistan", "巴基斯坦", "PKR"),
+    ("PL", "Poland", "波兰", "PLN"),
+    ("PM", "Saint Pierre and Miquelon", "圣皮埃尔和密克隆", "EUR"),
+    ("PN", "Pitcairn Islands", "皮特凯恩群岛", "NZD"),
+    ("PR", "Puerto Rico", "波多黎各", "USD"),
+    ("PS", "Palestine", "巴勒斯坦", "ILS"),
+    ("PT", "Portugal", "葡萄牙", "EUR"),
+    ("PW", "Palau", "帕劳", "USD"),
+    ("PY", "Paraguay", "巴拉圭", "PYG"),
+    ("QA", "Qatar", "卡塔尔", "QAR"),
+    ("RE", "Reunion", "留尼汪", "EUR"),
+    ("RO", "Romania", "罗马尼亚", "RON"),
+    ("RS", "Serbia", "塞尔维亚", "RSD"),
+    ("RU", "Russia", "俄罗斯", "RUB"),
+    ("RW", "Rwanda", "卢旺达", "RWF"),
+    ("SA", "Saudi Arabia", "沙特阿拉伯", "SAR"),
+    ("SB", "Solomon Islands", "所罗门群岛", "SBD"),
+    ("SC", "Seychelles", "塞舌尔", "SCR"),
+    ("SD", "Sudan", "苏丹", "SDG"),
+    ("SE", "Sweden", "瑞典", "SEK"),
+    ("SG", "Singapore", "新加坡", "SGD"),
+    ("SH", "Saint Helena", "圣赫勒拿", "SHP"),
+    ("SI", "Slovenia", "斯洛文尼亚", "EUR"),
+    ("SJ", "Svalbard and Jan Mayen", "斯瓦尔巴和扬马延", "NOK"),
+    ("SK", "Slovakia", "斯洛伐克", "EUR"),
+    ("SL", "Sierra Leone", "塞拉利昂", "SLE"),
+    ("SM", "San Marino", "圣马力诺", "EUR"),
+    ("SN", "Senegal", "塞内加尔", "XOF"),
+    ("SO", "Somalia", "索马里", "SOS"),
+    ("SR", "Suriname", "苏里南", "SRD"),
+    ("SS", "South Sudan", "南苏丹", "SSP"),
+    ("ST", "Sao Tome and Principe", "圣多美和普林西比", "STN"),
+    ("SV", "El Salvador", "萨尔瓦多", "USD"),
+    ("SX", "Sint Maarten", "荷属圣马丁", "XCG"),
+    ("SY", "Syria", "叙利亚", "SYP"),
+    ("SZ", "Eswatini", "斯威士兰", "SZL"),
+    ("TC", "Turks and Caicos Islands", "特克斯和凯科斯群岛", "USD"),
+    ("TD", "Chad", "乍得", "XAF"),
+    ("TG", "Togo", "多哥", "XOF"),
+    ("TH", "Thailand", "泰国", "THB"),
+    ("TJ", "Tajikistan", "塔吉克斯坦", "TJS"),
+    ("TK", "Tokelau", "托克劳", "NZD"),
+    ("TL", "Timor-Leste", "东帝汶", "USD"),
+    ("TM", "Turkmenistan", "土库曼斯坦", "TMT"),
+    ("TN", "Tunisia", "突尼斯", "TND"),
+    ("TO", "Tonga", "汤加", "TOP"),
+    ("TR", "Turkey", "土耳其", "TRY"),
+    ("TT", "Trinidad and Tobago", "特立尼达和多巴哥", "TTD"),
+    ("TV", "Tuvalu", "图瓦卢", "AUD"),
+    ("TW", "Taiwan", "台湾地区", "TWD"),
+    ("TZ", "Tanzania", "坦桑尼亚", "TZS"),
+    ("UA", "Ukraine", "乌克兰", "UAH"),
+    ("UG", "Uganda", "乌干达", "UGX"),
+    ("UM", "United States Minor Outlying Islands", "美国本土外小岛屿", "USD"),
+    ("US", "United States", "美国", "USD"),
+    ("UY", "Uruguay", "乌拉圭", "UYU"),
+    ("UZ", "Uzbekistan", "乌兹别克斯坦", "UZS"),
+    ("VA", "Vatican City", "梵蒂冈", "EUR"),
+    ("VC", "Saint Vincent and the Grenadines", "圣文森特和格林纳丁斯", "XCD"),
+    ("VE", "Venezuela", "委内瑞拉", "VES"),
+    ("VG", "British Virgin Islands", "英属维尔京群岛", "USD"),
+    ("VI", "US Virgin Islands", "美属维尔京群岛", "USD"),
+    ("VN", "Vietnam", "越南", "VND"),
+    ("VU", "Vanuatu", "瓦努阿图", "VUV"),
+    ("WF", "Wallis and Futuna", "瓦利斯和富图纳", "XPF"),
+    ("WS", "Samoa", "萨摩亚", "WST"),
+    ("XK", "Kosovo", "科索沃", "EUR"),
+    ("YE", "Yemen", "也门", "YER"),
+    ("YT", "Mayotte", "马约特", "EUR"),
+    ("ZA", "South Africa", "南非", "ZAR"),
+    ("ZM", "Zambia", "赞比亚", "ZMW"),
+    ("ZW", "Zimbabwe", "津巴布韦", "ZWG"),
+];
+
+// 使用该货币的国家/地区：两位代码、英文名和中文名
+fn currency_countries(code: &str) -> impl Iterator<Item = (&'static str, &'static str, &'static str)> + '_ {
+    COUNTRIES.iter().filter(move |(.., c)| *c == code).map(|&(alpha2, en, cn, _)| (alpha2, en, cn))
+}
+
+// 货币所属的地区，按此顺序排列；国际货币基金组织特别提款权（XDR）等不属于任何地区，排在最后
+static CURRENCY_REGIONS: &[(&str, &[&str])] = &[
+    (
+        "亚洲",
+        &[
+            "AED", "AFN", "AMD", "AZN", "BDT", "BHD", "BND", "BTN", "CNY", "GEL", "HKD", "IDR", "ILS", "INR",
+            "IQD", "IRR", "JOD", "JPY", "KGS", "KHR", "KRW", "KWD", "KZT", "LAK", "LBP", "LKR", "MMK", "MNT",
+            "MOP", "MVR", "MYR", "NPR", "OMR", "PHP", "PKR", "QAR", "SAR", "SGD", "SYP", "THB", "TJS", "TMT",
+            "TRY", "TWD", "UZS", "VND", "YER",
+        ],
+    ),
+    (
+        "欧洲",
+        &[
+            "ALL", "BAM", "BGN", "BYN", "CHF", "CZK", "DKK", "EUR", "FOK", "GBP", "GGP", "GIP", "HRK", "HUF",
+            "IMP", "ISK", "JEP", "MDL", "MKD", "NOK", "PLN", "RON", "RSD", "RUB", "SEK", "UAH",
+        ],
+    ),
+    (
+        "北美洲",
+        &[
+            "ANG", "AWG", "BBD", "BMD", "BSD", "BZD", "CAD", "CRC", "CUP", "DOP", "GTQ", "HNL", "HTG", "JMD",
+            "KYD", "MXN", "NIO", "PAB", "TTD", "USD", "XCD", "XCG",
+        ],
+    ),
+    (
+        "南美洲",
+        &[
+            "ARS", "BOB", "BRL", "CLP", "COP", "FKP", "GYD", "PEN", "PYG", "SRD", "UYU", "VEF", "VES",
+        ],
+    ),
+    (
+        "非洲",
+        &[
+            "AOA", "BIF", "BWP", "CDF", "CVE", "DJF", "DZD", "EGP", "ERN", "ETB", "GHS", "GMD", "GNF", "KES",
+            "KMF", "LRD", "LSL", "LYD", "MAD", "MGA", "MRU", "MUR", "MWK", "MZN", "NAD", "NGN", "RWF", "SCR",
+            "SDG", "SHP", "SLE", "SLL", "SOS", "SSP", "STN", "SZL", "TND", "TZS", "UGX", "XAF", "XOF", "ZAR",
+            "ZMW", "ZWG", "ZWL",
+        ],
+    ),
+    (
+        "大洋洲",
+        &[
+            "AUD", "FJD", "KID", "NZD", "PGK", "SBD", "TOP", "TVD", "VUV", "WST", "XPF",
+        ],
+    ),
+];
+
+// 地区的排列位置，没有地区时排在最后
+fn region_index(code: &str) -> usize {
+    CURRENCY_REGIONS.iter().position(|(_, codes)| codes.contains(&code)).unwrap_or(CURRENCY_REGIONS.len())
+}
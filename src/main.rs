@@ -1,48 +1,134 @@
 use std::{env, path::PathBuf};
 use currency_converter::api::fetch_rates;
+use currency_converter::config::Config;
 use currency_converter::formatter::{
-    convert_currency, show_all_currencies, show_error, show_instructions, show_source_currencies
+    convert_currency, convert_price, convert_sum, show_all_currencies, show_choices, show_error, show_instructions,
+    show_rates, show_source_currencies, show_table
 };
-use currency_converter::parser::parse_input;
+use currency_converter::model::Amount;
+use currency_converter::parser::parse_query;
+use currency_converter::query::ParseError;
+use currency_converter::usage::{normalize_code, Usage};
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let mut config = Config::from_env();
+
+    // --rates-file <路径>：使用固定汇率文件
+    if args.get(1).map(|s| s.as_str()) == Some("--rates-file") && args.len() > 2 {
+        config.rates_file = Some(PathBuf::from(args.remove(2)));
+        args.remove(1);
+    }
+
+    // --record <源货币> <目标货币>：记录选中的货币对，由工作流在选中结果后调用
+    if args.get(1).map(|s| s.as_str()) == Some("--record") && args.len() > 3 {
+        let Some(dir) = &config.data_dir else {
+            eprintln!("未设置 alfred_workflow_data，无法记录使用情况");
+            return;
+        };
+        let (Some(src), Some(dst)) = (normalize_code(&args[2]), normalize_code(&args[3])) else {
+            eprintln!("无效的货币代码: {} {}", args[2], args[3]);
+            return;
+        };
+        let mut usage = Usage::load(Some(dir));
+        usage.record(&src, &dst);
+        if let Err(e) = usage.save(dir) {
+            eprintln!("保存使用记录失败: {}", e);
+        }
+        return;
+    }
+    let usage = Usage::load(config.data_dir.as_deref());
+
     let input = args.get(1).map(|s| s.as_str()).unwrap_or_default();
 
     // 获取缓存路径
     // ~/Library/Caches/com.runningwithcrayons.Alfred/Workflow Data/com.alfredapp.currency-converter
     // let cache_dir = env::var("alfred_workflow_cache").unwrap();
     let cache_dir = String::from("./cache");
-    let cache_path = PathBuf::from(cache_dir).join("ratesUSD.json");
+    let cache_path = PathBuf::from(&cache_dir).join("ratesUSD.json");
 
     // 获取汇率数据
-    let currencies = match fetch_rates(&cache_path) {
+    let currencies = match fetch_rates(&cache_path, &config) {
         Ok(c) => c,
         Err(e) => {
-            show_error(&format!("获取汇率失败: {}", e));
+            println!("{}", show_error(&format!("获取汇率失败: {}", e)));
             return;
         }
     };
 
     // 解析输入
-    let (raw_num, parts) = parse_input(input);
-    let number = match raw_num.parse::<f64>() {
-        Ok(n) if n > 0.0 => n,
-        _ => {
-            show_instructions();
+    let query = match parse_query(input, &config) {
+        Ok(query) => query,
+        Err(ParseError::Empty) => {
+            println!("{}", show_instructions());
+            return;
+        }
+        Err(e) => {
+            println!("{}", show_error(&e.to_string()));
             return;
         }
     };
 
-    // 处理不同阶段
-    let output = match parts.as_slice() {
-        [] => show_all_currencies(number, &currencies),
-        [src] => show_source_currencies(number, src, &currencies),
-        [src, dst] => convert_currency(number, src, dst, &currencies),
-        _ => {
-            show_error("无效输入格式");
+    // 暂无历史汇率数据源
+    if let Some(date) = query.date {
+        println!("{}", show_error(&format!("暂不支持历史汇率（{}）", date)));
+        return;
+    }
+
+    // 名称对应多种货币时先选择货币；单个目标货币的换算会列出所有匹配的目标，不需要选择
+    let term = query.term();
+    let lists_targets = query.terms.len() == 1
+        && query.table.is_none()
+        && query.targets.len() == 1
+        && term.amount.is_some()
+        && term.source.as_ref().is_some_and(|src| src.unit.is_none());
+    let sources = query.terms.iter().filter_map(|term| term.source.as_ref());
+    let targets = query.targets.iter().filter(|_| !lists_targets);
+    if let Some(output) = show_choices(input, &sources.chain(targets).collect::<Vec<_>>(), &currencies, &config, &usage) {
+        println!("{}", output);
+        return;
+    }
+
+    // 多币种求和
+    if query.terms.len() > 1 {
+        println!("{}", convert_sum(&query.terms, &query.targets, &currencies, &config, &usage));
+        return;
+    }
+
+    // 对照表，预设档位乘以输入的金额
+    if let Some(table) = &query.table {
+        let output = match &term.source {
+            Some(src) => {
+                let amounts = table.amounts(term.amount.as_ref().map_or(1.0, |a| a.value));
+                // 固定汇率模式不写缓存目录
+                let quicklook_path = PathBuf::from(&cache_dir).join("table.txt");
+                let quicklook_path = config.rates_file.is_none().then_some(quicklook_path.as_path());
+                show_table(&amounts, src, &query.targets, &currencies, quicklook_path, &config, &usage)
+            }
+            None => show_instructions(),
+        };
+        println!("{}", output);
+        return;
+    }
+
+    // 未输入金额时按 1 计算
+    let number = match &term.amount {
+        Some(amount) if amount.value > 0.0 => amount.clone(),
+        Some(_) => {
+            println!("{}", show_instructions());
             return;
         }
+        None => Amount::new(1.0, "1"),
+    };
+    let number = &number;
+
+    // 处理不同阶段
+    let output = match (&term.source, query.targets.as_slice()) {
+        (None, _) => show_all_currencies(number, &currencies, &config, &usage),
+        (Some(src), dsts) if src.unit.is_some() => convert_price(number, src, dsts, &currencies, &config, &usage),
+        (Some(src), dsts) if term.amount.is_none() && !dsts.is_empty() => show_rates(src, dsts, &currencies, &config, &usage),
+        (Some(src), []) => show_source_currencies(number, src, &currencies, &config, &usage),
+        (Some(src), dsts) => convert_currency(number, src, dsts, &currencies, &config, &usage),
     };
 
     println!("{}", output);
@@ -1,32 +1,415 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use crate::config::{Config, Language, SortOrder};
 use crate::model::CurrencyInfo;
-use crate::PRIORITY;
+use crate::usage::Usage;
+use crate::{all_currency_names, currency_countries, currency_names, region_index, CURRENCY_ALIASES, PRIORITY};
 
+// 匹配程度，越小越靠前
+const EXACT: u8 = 0; // 代码、国家/地区代码、别名或名称完全相同
+const CODE_PREFIX: u8 = 1;
+const NAME_PREFIX: u8 = 2; // 别名或名称的前缀，英文名称可从任一单词开头
+const COUNTRY_PREFIX: u8 = 3; // 使用该货币的国家/地区名的前缀，避免 franc 与 france 的欧元并列
+const SUBSTRING: u8 = 4;
+const TYPO: u8 = 5; // 拼写错误，加上差异程度，只在没有其他匹配时使用
+
+// 按匹配程度排序，相同时按配置的排列方式
 pub fn match_currencies<'a>(
     search: &str,
     currencies: &'a HashMap<String, CurrencyInfo>,
+    config: &Config,
+    usage: &Usage,
 ) -> Vec<(&'a String, &'a CurrencyInfo)> {
-    let search = search.to_lowercase();
-    let mut matched = Vec::new();
+    scored_matches(search, currencies, config, usage).into_iter().map(|(_, code, info)| (code, info)).collect()
+}
 
-    for (code, info) in currencies {
-        if code.to_lowercase().starts_with(&search) {
-            matched.push((code, info));
-            continue;
-        }
+// 匹配程度最高的一组货币，如 dollar 同样匹配 USD、HKD、BND
+pub fn best_matches<'a>(
+    search: &str,
+    currencies: &'a HashMap<String, CurrencyInfo>,
+    config: &Config,
+    usage: &Usage,
+) -> Vec<(&'a String, &'a CurrencyInfo)> {
+    best_scored(search, currencies, config, usage).into_iter().map(|(_, code, info)| (code, info)).collect()
+}
 
-        let coin_name = format!("{}s", info.coin).to_lowercase();
-        if coin_name.starts_with(&search) {
-            matched.push((code, info));
+// 名称有歧义时可选的货币，如 比索、dollar、元；只算完全相同或名称前缀的并列，
+// c 这样同为代码前缀的并列不算歧义，没有歧义时为空
+pub fn ambiguous_matches<'a>(
+    search: &str,
+    currencies: &'a HashMap<String, CurrencyInfo>,
+    config: &Config,
+    usage: &Usage,
+) -> Vec<(&'a String, &'a CurrencyInfo)> {
+    let matched = best_scored(search, currencies, config, usage);
+    match matched.first() {
+        Some(&(EXACT | NAME_PREFIX, _, _)) if matched.len() > 1 => {
+            matched.into_iter().map(|(_, code, info)| (code, info)).collect()
         }
+        _ => Vec::new(),
+    }
+}
+
+fn best_scored<'a>(
+    search: &str,
+    currencies: &'a HashMap<String, CurrencyInfo>,
+    config: &Config,
+    usage: &Usage,
+) -> Vec<(u8, &'a String, &'a CurrencyInfo)> {
+    let matched = scored_matches(search, currencies, config, usage);
+    let rank = |&(score, _, info): &(u8, _, &CurrencyInfo)| (score, info.retirement.is_some());
+    let best = matched.first().map(rank);
+    matched.into_iter().take_while(|m| Some(rank(m)) == best).collect()
+}
+
+fn scored_matches<'a>(
+    search: &str,
+    currencies: &'a HashMap<String, CurrencyInfo>,
+    config: &Config,
+    usage: &Usage,
+) -> Vec<(u8, &'a String, &'a CurrencyInfo)> {
+    let search = search.to_lowercase();
+    let aliases = aliases(config);
+    let mut matched: Vec<_> = currencies
+        .iter()
+        .filter_map(|(code, info)| score(&search, code, info, &aliases).map(|score| (score, code, info)))
+        .collect();
+
+    // 只有拼写错误时只保留最接近的，如 eru 匹配 EUR 而不匹配 ERN
+    match matched.iter().map(|&(score, _, _)| score).min() {
+        Some(best) if best < TYPO => matched.retain(|&(score, _, _)| score < TYPO),
+        Some(best) => matched.retain(|&(score, _, _)| score == best),
+        None => {}
     }
 
-    // 优先排序常用货币
+    // 同样匹配时已停用的货币在后，如 利昂 优先 SLE
     matched.sort_by(|a, b| {
-        let a_prio = PRIORITY.iter().position(|&c| c == a.0).unwrap_or(100);
-        let b_prio = PRIORITY.iter().position(|&c| c == b.0).unwrap_or(100);
-        a_prio.cmp(&b_prio)
+        a.0.cmp(&b.0)
+            .then(a.2.retirement.is_some().cmp(&b.2.retirement.is_some()))
+            .then_with(|| list_order(a.1, b.1, None, config, usage))
     });
-
     matched
-}
\ No newline at end of file
+}
+
+// 按配置的排列方式排序货币列表，src 为源货币时目标货币列表会考虑与它的组合
+pub fn sort_currencies(
+    currencies: &mut [(&String, &CurrencyInfo)],
+    src: Option<&str>,
+    config: &Config,
+    usage: &Usage,
+) {
+    currencies.sort_by(|a, b| list_order(a.0, b.0, src, config, usage));
+}
+
+// 优先货币在前，其余按排列方式，最后按代码，保证每次输入时顺序不变
+fn list_order(a: &str, b: &str, src: Option<&str>, config: &Config, usage: &Usage) -> Ordering {
+    let priority = |code| PRIORITY.iter().position(|&c| c == code).unwrap_or(PRIORITY.len());
+    let order = match config.sort_order {
+        SortOrder::Frecency => return usage_order(a, b, src, usage),
+        SortOrder::Code => Ordering::Equal,
+        SortOrder::ChineseName => name_order(a, b, Language::ZhHans),
+        SortOrder::EnglishName => name_order(a, b, Language::En),
+        SortOrder::Region => region_index(a).cmp(&region_index(b)),
+    };
+    priority(a).cmp(&priority(b)).then(order).then(a.cmp(b))
+}
+
+// 按国家/地区名、货币名排序，没有名称的货币在后
+fn name_order(a: &str, b: &str, language: Language) -> Ordering {
+    let (a, b) = (currency_names(a, language), currency_names(b, language));
+    a.is_none().cmp(&b.is_none()).then(a.cmp(&b))
+}
+
+// 常用货币在前：先看与源货币的组合和货币本身的使用记录，再看优先货币列表，最后按代码
+fn usage_order(a: &str, b: &str, src: Option<&str>, usage: &Usage) -> Ordering {
+    let pair = |code| src.map_or(0.0, |src| usage.pair_score(src, code));
+    let priority = |code| PRIORITY.iter().position(|&c| c == code).unwrap_or(PRIORITY.len());
+    pair(b)
+        .total_cmp(&pair(a))
+        .then(usage.currency_score(b).total_cmp(&usage.currency_score(a)))
+        .then(priority(a).cmp(&priority(b)))
+        .then(a.cmp(b))
+}
+
+// 计算一种货币的匹配程度，不匹配时返回 None
+// 名称包括所有语言的国家/地区名和货币名，中文和日文另有两者连写，币和元结尾视为相同（港币 = 港元）
+// 另有使用该货币的国家/地区，如 de、germany、德国 都匹配欧元
+// 国家/地区名只按完全相同和前缀匹配，避免 泰 匹配到 圣巴泰勒米
+fn score(search: &str, code: &str, info: &CurrencyInfo, aliases: &[(&str, &str)]) -> Option<u8> {
+    let code_lower = code.to_lowercase();
+    let countries: Vec<_> = currency_countries(code).collect();
+    let mut names: Vec<(&str, &str)> = all_currency_names(code).collect();
+    names.push((&info.country, &info.coin));
+    let mut names: Vec<String> = names
+        .iter()
+        .flat_map(|&(country, coin)| {
+            let full_name = (!country.is_ascii()).then(|| format!("{}{}", country, coin));
+            [Some(country.to_lowercase()), Some(coin.to_lowercase()), full_name]
+        })
+        .flatten()
+        .filter(|name| !name.is_empty())
+        .collect();
+    let alias_names = aliases.iter().filter(|(_, c)| *c == code).map(|(alias, _)| alias.to_string());
+    names.extend(alias_names);
+    let country_names: Vec<String> =
+        countries.iter().flat_map(|&(_, en, cn)| [en.to_lowercase(), cn.to_string()]).collect();
+
+    let is_country_code = countries.iter().any(|(alpha2, ..)| alpha2.eq_ignore_ascii_case(search));
+    if code_lower == search || is_country_code || names.iter().chain(&country_names).any(|name| name == search) {
+        return Some(EXACT);
+    }
+    if code_lower.starts_with(search) {
+        return Some(CODE_PREFIX);
+    }
+
+    if search.is_ascii() {
+        // 英文名称和别名至少输入两个字母
+        if search.len() < 2 {
+            return None;
+        }
+        let word_prefix = |name: &String| name.starts_with(search) || name.contains(&format!(" {}", search));
+        if names.iter().any(word_prefix) {
+            return Some(NAME_PREFIX);
+        }
+        if country_names.iter().any(word_prefix) {
+            return Some(COUNTRY_PREFIX);
+        }
+        // 词中包含至少四个字母，避免 eru 匹配到 peru
+        if search.len() >= 4 && names.iter().any(|name| name.contains(search)) {
+            return Some(SUBSTRING);
+        }
+        // 名称的前缀（如 aruba 的 aru）不如完整的代码、名称或单词可信，差异程度加一
+        let candidates = names.iter().flat_map(|name| {
+            let prefix: String = name.chars().take(search.chars().count()).collect();
+            let words = name.split(' ').map(|word| (word.to_string(), 0));
+            [(name.clone(), 0), (prefix, 1)].into_iter().chain(words)
+        });
+        return std::iter::once((code_lower, 0))
+            .chain(candidates)
+            .filter_map(|(candidate, penalty)| typo_cost(search, &candidate).map(|cost| cost + penalty))
+            .min()
+            .map(|cost| TYPO + cost as u8);
+    }
+
+    let stem = name_stem(search);
+    if names.iter().any(|name| name.starts_with(search) || name_stem(name).starts_with(stem)) {
+        return Some(NAME_PREFIX);
+    }
+    if country_names.iter().any(|name| name.starts_with(search)) {
+        return Some(COUNTRY_PREFIX);
+    }
+    // 去掉后缀的词干只用于前缀，否则 美元 的 美 会匹配到 亚美尼亚
+    if names.iter().any(|name| name.contains(search)) {
+        return Some(SUBSTRING);
+    }
+    None
+}
+
+// 去掉币、元后缀，只剩后缀时保留原样
+fn name_stem(name: &str) -> &str {
+    match name.trim_end_matches(['币', '幣', '元']) {
+        "" => name,
+        stem => stem,
+    }
+}
+
+// 生效的别名：自定义别名覆盖同名的内置别名
+fn aliases(config: &Config) -> Vec<(&str, &str)> {
+    let custom = config.currency_aliases.iter().map(|(alias, code)| (alias.as_str(), code.as_str()));
+    let builtin = CURRENCY_ALIASES
+        .iter()
+        .filter(|(alias, _)| !config.currency_aliases.iter().any(|(a, _)| a == alias))
+        .copied();
+    custom.chain(builtin).collect()
+}
+
+// 是否为拼写错误：至少三个字母，四个字母以内允许错一处，更长的允许错两处
+pub(crate) fn is_typo(search: &str, candidate: &str) -> bool {
+    typo_cost(search, candidate).is_some()
+}
+
+// 拼写错误的差异程度，用于排序：相邻字母对调最常见，算 1，其他每处算 2
+fn typo_cost(search: &str, candidate: &str) -> Option<usize> {
+    let len = search.chars().count();
+    let limit = if len <= 4 { 1 } else { 2 };
+    let is_typo = len >= 3
+        && candidate.chars().count().abs_diff(len) <= limit
+        && edit_distance(search, candidate, 1) <= limit;
+    is_typo.then(|| edit_distance(search, candidate, 2))
+}
+
+// 编辑距离，增删改每处算 step，相邻字母对调算 1（如 eru → eur）
+fn edit_distance(a: &str, b: &str, step: usize) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![(0..=b.len()).map(|j| j * step).collect::<Vec<_>>(); a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i * step;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { step };
+            let mut distance = (rows[i - 1][j] + step).min(rows[i][j - 1] + step).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use crate::config::{Config, SortOrder};
+    use crate::matcher::{ambiguous_matches, best_matches, match_currencies, sort_currencies};
+    use crate::model::CurrencyInfo;
+    use crate::usage::Usage;
+
+    fn currencies() -> HashMap<String, CurrencyInfo> {
+        [
+            ("USD", "美国", "美元"),
+            ("CNY", "中国", "人民币"),
+            ("GBP", "英国", "英镑"),
+            ("LAK", "老挝", "基普"),
+            ("HKD", "香港", "港元"),
+            ("JPY", "日本", "日元"),
+            ("THB", "泰国", "铢"),
+            ("EUR", "欧盟", "欧元"),
+            ("CHF", "瑞士", "法郎"),
+            ("AMD", "亚美尼亚", "德拉姆"),
+            ("STN", "圣多美和普林西比", "多布拉"),
+            ("NGN", "尼日利亚", "奈拉"),
+            ("AWG", "阿鲁巴", "弗罗林"),
+            ("BND", "文莱", "元"),
+            ("ERN", "厄立特里亚", "纳克法"),
+        ]
+        .iter()
+        .map(|(code, country, coin)| (code.to_string(), CurrencyInfo::new(1.0, country.to_string(), coin.to_string())))
+        .collect()
+    }
+
+    fn codes(search: &str, config: &Config) -> Vec<String> {
+        let currencies = currencies();
+        match_currencies(search, &currencies, config, &Usage::default()).into_iter().map(|(code, _)| code.clone()).collect()
+    }
+
+    #[test]
+    fn test_aliases() {
+        let config = Config::default();
+        assert_eq!(codes("rmb", &config), vec!["CNY"]);
+        assert_eq!(codes("meiy", &config), vec!["USD"]);
+        assert_eq!(codes("刀", &config), vec!["USD"]);
+        assert_eq!(codes("quid", &config), vec!["GBP"]);
+
+        // 自定义别名覆盖内置别名
+        let config = Config {
+            currency_aliases: vec![("lao".into(), "LAK".into()), ("kuai".into(), "USD".into())],
+            ..Config::default()
+        };
+        assert_eq!(codes("lao", &config), vec!["LAK"]);
+        assert_eq!(codes("kuai", &config), vec!["USD"]);
+    }
+
+    #[test]
+    fn test_chinese_names() {
+        let config = Config::default();
+        assert_eq!(codes("日本", &config), vec!["JPY"]);
+        assert_eq!(codes("日元", &config), vec!["JPY"]);
+        assert_eq!(codes("美元", &config), vec!["USD"]);
+        assert_eq!(codes("泰", &config), vec!["THB"]);
+        assert_eq!(codes("港币", &config), vec!["HKD"]);
+        assert_eq!(codes("民币", &config), vec!["CNY"]);
+        assert_eq!(codes("英国英镑", &config), vec!["GBP"]);
+    }
+
+    #[test]
+    fn test_ranking() {
+        let config = Config::default();
+        // 完全匹配优先于前缀匹配，前缀匹配优先于包含
+        assert_eq!(codes("us", &config)[0], "USD");
+        assert_eq!(codes("元", &config), vec!["CNY", "BND", "USD", "EUR", "HKD", "JPY"]);
+        assert_eq!(codes("eu", &config)[0], "EUR");
+
+        // 拼写错误，有其他匹配时不使用
+        assert_eq!(codes("eru", &config), vec!["EUR"]);
+        assert_eq!(codes("yne", &config), vec!["JPY"]);
+        assert_eq!(codes("swiss frnc", &config), vec!["CHF"]);
+        assert_eq!(codes("japnese", &config), vec!["JPY"]);
+        assert_eq!(codes("usd", &config), vec!["USD"]);
+        assert_eq!(codes("ollar", &config), vec!["USD", "HKD", "BND"]);
+
+        // 使用记录优先于优先货币列表
+        let mut usage = Usage::default();
+        usage.record("HKD", "JPY");
+        let currencies = currencies();
+        let matched = match_currencies("dollar", &currencies, &config, &usage);
+        assert_eq!(matched.iter().map(|(code, _)| code.as_str()).collect::<Vec<_>>(), vec!["HKD", "USD", "BND"]);
+    }
+
+    #[test]
+    fn test_best_matches() {
+        let config = Config::default();
+        let usage = Usage::default();
+        let currencies = currencies();
+        let best = |search| {
+            best_matches(search, &currencies, &config, &usage).into_iter().map(|(code, _)| code.as_str()).collect::<Vec<_>>()
+        };
+        // 名称有歧义时返回所有同样匹配的货币
+        assert_eq!(best("dollar"), vec!["USD", "HKD", "BND"]);
+        // 拼写错误按差异程度排序，只有最接近的才算并列
+        assert_eq!(best("eru"), vec!["EUR"]);
+        // 货币名优先于国家/地区名，franc 不与 france 的欧元并列
+        assert_eq!(best("franc"), vec!["CHF"]);
+        assert_eq!(best("us"), vec!["USD"]);
+
+        // 只有名称并列才算歧义，同为代码前缀的 c 不算
+        assert_eq!(ambiguous_matches("dollar", &currencies, &config, &usage).len(), 3);
+        assert!(best("c").len() > 1);
+        assert!(ambiguous_matches("c", &currencies, &config, &usage).is_empty());
+    }
+
+    #[test]
+    fn test_other_languages() {
+        let config = Config::default();
+        assert_eq!(codes("japanese", &config), vec!["JPY"]);
+        assert_eq!(codes("thai baht", &config), vec!["THB"]);
+        assert_eq!(codes("hong kong", &config), vec!["HKD"]);
+        assert_eq!(codes("港幣", &config), vec!["HKD"]);
+        assert_eq!(codes("米ドル", &config), vec!["USD"]);
+        assert_eq!(codes("pound", &config), vec!["GBP"]);
+    }
+
+    #[test]
+    fn test_countries() {
+        let config = Config::default();
+        // 国家/地区代码完全匹配，优先于名称前缀
+        assert_eq!(codes("de", &config)[0], "EUR");
+        assert_eq!(codes("jp", &config), vec!["JPY"]);
+        assert_eq!(codes("germany", &config), vec!["EUR"]);
+        assert_eq!(codes("德国", &config), vec!["EUR"]);
+        assert_eq!(codes("ecuador", &config), vec!["USD"]);
+        assert_eq!(codes("liechtenstein", &config), vec!["CHF"]);
+        assert_eq!(codes("uk", &config), vec!["GBP"]);
+    }
+
+    #[test]
+    fn test_sort_order() {
+        let currencies = currencies();
+        let sorted = |sort_order| {
+            let config = Config { sort_order, ..Config::default() };
+            let mut list: Vec<_> = currencies.iter().collect();
+            sort_currencies(&mut list, None, &config, &Usage::default());
+            list[6..].iter().map(|(code, _)| code.as_str()).collect::<Vec<_>>()
+        };
+        // 优先货币之后的部分按配置排列
+        let by_code = vec!["AMD", "AWG", "BND", "CHF", "ERN", "LAK", "NGN", "STN", "THB"];
+        assert_eq!(sorted(SortOrder::Frecency), by_code);
+        assert_eq!(sorted(SortOrder::Code), by_code);
+        assert_eq!(sorted(SortOrder::ChineseName), vec!["AMD", "ERN", "STN", "NGN", "BND", "THB", "CHF", "LAK", "AWG"]);
+        assert_eq!(sorted(SortOrder::EnglishName), vec!["AMD", "AWG", "BND", "ERN", "LAK", "NGN", "STN", "CHF", "THB"]);
+        assert_eq!(sorted(SortOrder::Region), vec!["AMD", "BND", "LAK", "THB", "CHF", "AWG", "ERN", "NGN", "STN"]);
+    }
+}
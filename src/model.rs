@@ -1,3 +1,4 @@
+use std::fmt;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -5,6 +6,17 @@ pub struct CurrencyInfo {
     pub rate: f64,
     pub country: String,
     pub coin: String,
+    #[serde(skip)]
+    pub retirement: Option<&'static Retirement>, // 已停用时的替代信息，不写入缓存
+}
+
+// 已停用的货币：自 since 起由 successor 取代，1 successor = per_successor 旧货币
+#[derive(Debug, PartialEq)]
+pub struct Retirement {
+    pub code: &'static str,
+    pub successor: &'static str,
+    pub per_successor: f64,
+    pub since: &'static str,
 }
 
 impl CurrencyInfo {
@@ -13,6 +25,48 @@ impl CurrencyInfo {
             rate,
             country,
             coin,
+            retirement: None,
         }
     }
-}
\ No newline at end of file
+
+    // 显示名称：国家/地区 + 货币名，缺少国家时只显示货币名
+    pub fn display_name(&self) -> String {
+        format!("{} {}", self.country, self.coin).trim().to_string()
+    }
+}
+
+// 金额，保留非纯数字输入的原始表达式用于回显
+#[derive(Debug, Clone, PartialEq)]
+pub struct Amount {
+    pub value: f64,
+    pub expression: Option<String>,
+}
+
+impl Amount {
+    pub fn new(value: f64, input: &str) -> Self {
+        let expression = match input.parse::<f64>() {
+            Ok(_) => None,
+            Err(_) => Some(input.to_string()),
+        };
+        Self { value, expression }
+    }
+
+    // 副标题前缀，如 "(120+35.5)*1.08 = 167.94 · "
+    pub fn echo(&self) -> String {
+        match &self.expression {
+            // 去掉浮点误差，如 167.94000000000003
+            Some(expr) => format!("{} = {} · ", expr, (self.value * 1e10).round() / 1e10),
+            None => String::new(),
+        }
+    }
+}
+
+impl fmt::Display for Amount {
+    // 自动补全时保留原表达式
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.expression {
+            Some(expr) => write!(f, "{}", expr),
+            None => write!(f, "{}", self.value),
+        }
+    }
+}
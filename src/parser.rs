@@ -1,93 +1,750 @@
+use chrono::NaiveDate;
 use regex::Regex;
+use crate::{COUNTRIES, CURRENCY_NAMES_CN, CURRENCY_NAMES_EN};
+use crate::config::Config;
+use crate::expr::{evaluate, expression_end};
+use crate::matcher::is_typo;
+use crate::model::Amount;
+use crate::query::{CurrencyRef, ParseError, Query, Table, Term, MAX_TABLE_ROWS};
+use crate::units::{find_unit, unit_prefix_len};
 
-// 解析输入
-pub fn parse_input(input: &str) -> (String, Vec<String>) {
-    // 步骤1：清理输入并提取数字部分
-    let cleaned = input.replace(|c: char| c.is_ascii_punctuation() && c != '.' && c != '-', " ")
-        .to_lowercase();
-
-    // 步骤2：使用正则表达式精确匹配数字和货币代码的组合
-    let re = Regex::new(r"(?x)
-        ^
-        (\d*\.?\d+)    # 匹配数字（含小数）
-        ([a-z]{1,3})     # 匹配1到3字母货币代码
-        (?:\s+([a-z]+))? # 可选的其他货币代码
-        $
-    ").unwrap();
-
-    if let Some(caps) = re.captures(&cleaned) {
-        // 处理类似 "14.1USD" 的情况
-        let num_str = caps.get(1).unwrap().as_str();
-        let first_code = caps.get(2).unwrap().as_str();
-        let second_code = caps.get(3).map(|m| m.as_str()).unwrap_or_default();
-
-        return (
-            num_str.to_string(),
-            vec![first_code.to_string(), second_code.to_string()]
-                .into_iter()
-                .filter(|s| !s.is_empty())
-                .collect()
-        );
+// 货币符号（小写），带前缀的符号须排在 $ 之前；$ 和 ¥ 的含义由配置决定
+const SYMBOLS: &[(&str, &str)] = &[
+    ("us$", "USD"),
+    ("hk$", "HKD"),
+    ("nt$", "TWD"),
+    ("r$", "BRL"),
+    ("au$", "AUD"),
+    ("a$", "AUD"),
+    ("ca$", "CAD"),
+    ("c$", "CAD"),
+    ("s$", "SGD"),
+    ("nz$", "NZD"),
+    ("mx$", "MXN"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("₩", "KRW"),
+    ("₹", "INR"),
+    ("₽", "RUB"),
+    ("฿", "THB"),
+    ("₺", "TRY"),
+    ("₫", "VND"),
+    ("₱", "PHP"),
+    ("₪", "ILS"),
+    ("₴", "UAH"),
+    ("₦", "NGN"),
+    ("円", "JPY"),
+];
+
+// 匹配开头的货币符号，返回货币代码（小写）和符号长度
+fn match_symbol(input: &str, config: &Config) -> Option<(String, usize)> {
+    if let Some((symbol, code)) = SYMBOLS.iter().find(|(symbol, _)| input.starts_with(symbol)) {
+        return Some((code.to_lowercase(), symbol.len()));
     }
 
-    // 步骤3：处理其他格式（带空格分隔）
-    let mut parts: Vec<&str> = cleaned.split_whitespace().collect();
+    let c = input.chars().next()?;
+    let code = match c {
+        '$' | '＄' => &config.dollar_symbol,
+        '¥' | '￥' => &config.yen_symbol,
+        _ => return None,
+    };
+    Some((code.to_lowercase(), c.len_utf8()))
+}
 
-    // 处理类似 "100USD" 的情况
-    if parts.len() == 1 {
-        let re_compact = Regex::new(r"^(\d+\.?\d*)([a-z]{3})$").unwrap();
-        if let Some(caps) = re_compact.captures(parts[0]) {
-            return (
-                caps.get(1).unwrap().as_str().to_string(),
-                vec![caps.get(2).unwrap().as_str().to_string()]
-            );
+// 源货币与目标货币之间的连接词
+const CONNECTOR_WORDS: &[&str] = &["to", "in", "into", "as"];
+const CONNECTOR_ARROWS: &[&str] = &["->", "=>", "→", "⇒", "="];
+// 中文连接词可与货币名连写，如 100美元换人民币，较长的在前
+const CONNECTORS_CN: &[&str] = &["兑换成", "转换成", "换算成", "兑换", "转换", "换算", "换成", "兑成", "转成", "等于", "换", "兑", "转"];
+
+// 按中文连接词拆分，跳过货币名称中的部分（如 可兑换马克）
+fn split_connectors(word: &str) -> Vec<&str> {
+    let protected: Vec<(usize, usize)> = CURRENCY_NAMES_CN
+        .iter()
+        .flat_map(|(_, (country, coin))| [*country, *coin])
+        .flat_map(|name| word.match_indices(name).map(|(i, m)| (i, i + m.len())))
+        .collect();
+
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut pos = 0;
+    while pos < word.len() {
+        let connector = CONNECTORS_CN
+            .iter()
+            .find(|c| word[pos..].starts_with(**c))
+            .filter(|c| !protected.iter().any(|&(a, b)| pos < b && pos + c.len() > a));
+        match connector {
+            Some(connector) => {
+                pieces.push(&word[start..pos]);
+                pos += connector.len();
+                start = pos;
+            }
+            None => pos += word[pos..].chars().next().map_or(1, char::len_utf8),
         }
     }
+    pieces.push(&word[start..]);
+    pieces.into_iter().filter(|p| !p.is_empty()).collect()
+}
 
-    // 步骤4：常规解析
-    let (num_part, rest): (Vec<&str>, Vec<&str>) = parts
-        .iter()
-        .cloned()
-        .partition(|s| s.parse::<f64>().is_ok());
+// 对照表关键词：步长和预设档位
+const STEP_WORDS: &[&str] = &["step", "by", "步长", "每"];
+const TABLE_WORDS: &[&str] = &["table", "对照表"];
+
+// 千位分隔符：撇号和各种空格只用作分组
+const GROUP_SEPARATORS: &[char] = &['\'', '’', ' ', '\u{a0}', '\u{202f}'];
+
+// 规范化后的输入：小写并统一数字格式，offsets 记录每个字节在原输入中的位置
+struct Normalized<'a> {
+    input: &'a str,
+    text: String,
+    offsets: Vec<usize>,
+}
+
+impl Normalized<'_> {
+    // 规范化文本中的字节位置对应的原输入字符位置（从 1 开始）
+    fn position(&self, offset: usize) -> usize {
+        let original = self.offsets.get(offset).copied().unwrap_or(self.input.len());
+        self.input[..original].chars().count() + 1
+    }
+}
+
+// 将带千位分隔符或小数逗号的数字统一为 1234.56 的形式
+// 如 1,234.56、1.234,56、1 234,56、12'345.00
+fn normalize_input<'a>(input: &'a str, config: &Config) -> Normalized<'a> {
+    let mut lower = String::new();
+    let mut lower_offsets = Vec::new();
+    for (i, c) in input.char_indices() {
+        for l in c.to_lowercase() {
+            lower.push(l);
+            lower_offsets.extend(std::iter::repeat_n(i, l.len_utf8()));
+        }
+    }
+
+    let re = Regex::new(r"\d+(?:[,.'’ \x{a0}\x{202f}]\d+)+").unwrap();
+    let mut text = String::new();
+    let mut offsets = Vec::new();
+    let mut last = 0;
+    for m in re.find_iter(&lower) {
+        let Some(number) = normalize_number(m.as_str(), config) else { continue };
+        text.push_str(&lower[last..m.start()]);
+        offsets.extend_from_slice(&lower_offsets[last..m.start()]);
+        text.push_str(&number);
+        offsets.extend(std::iter::repeat_n(lower_offsets[m.start()], number.len()));
+        last = m.end();
+    }
+    text.push_str(&lower[last..]);
+    offsets.extend_from_slice(&lower_offsets[last..]);
+
+    Normalized { input, text, offsets }
+}
+
+fn normalize_number(literal: &str, config: &Config) -> Option<String> {
+    let groups: Vec<&str> = literal.split(|c: char| !c.is_ascii_digit()).collect();
+    let separators: Vec<char> = literal.chars().filter(|c| !c.is_ascii_digit()).collect();
+    let last = *separators.last()?;
+    let marks = separators.iter().filter(|c| !GROUP_SEPARATORS.contains(c)).count();
+
+    // 判断小数点：点和逗号都出现时取最后一个；只出现一次时结合配置和位数判断
+    let decimal = match config.decimal_separator {
+        _ if GROUP_SEPARATORS.contains(&last) => None,
+        Some(decimal) => Some(decimal).filter(|&d| d == last),
+        None if separators.contains(&'.') && separators.contains(&',') => Some(last),
+        None if marks > 1 => None,
+        // 单个逗号后跟三位数字视为千位分隔，如 1,234
+        None if last == ',' && groups[groups.len() - 1].len() == 3 => None,
+        None => Some(last),
+    };
+
+    let (integer, fraction) = match decimal {
+        Some(_) => (&groups[..groups.len() - 1], groups.last().copied()),
+        None => (&groups[..], None),
+    };
+    let group_separators = match decimal {
+        Some(_) => &separators[..separators.len() - 1],
+        None => &separators[..],
+    };
+
+    // 分组须一致且每组三位，否则不是合法的分组数字
+    if group_separators.iter().any(|&c| Some(c) == decimal || c != group_separators[0])
+        || integer[0].len() > 3 && integer.len() > 1
+        || integer[1..].iter().any(|g| g.len() != 3)
+    {
+        return None;
+    }
+
+    let mut number = integer.concat();
+    if let Some(fraction) = fraction {
+        number.push('.');
+        number.push_str(fraction);
+    }
+    Some(number)
+}
+
+#[derive(Debug, PartialEq)]
+enum TokenKind {
+    Amount,
+    Currency,
+    Plus,
+    Date,
+    Range,
+    Step,
+    Table,
+    Connector,
+}
+
+// 词法单元，offset 为在规范化文本中的字节位置
+struct Token {
+    kind: TokenKind,
+    text: String,
+    offset: usize,
+}
+
+// 拆分词法单元：金额表达式、范围、货币符号、日期、+、关键词和货币词
+fn tokenize(text: &str, config: &Config) -> Vec<Token> {
+    // 日期须以 @、on 或 at 开头，否则 2000-12-5 按减法计算
+    let date_re = Regex::new(r"^(?:@\s*|(?:on|at)\s+)(\d{4}-\d{1,2}-\d{1,2})").unwrap();
+    // 范围用 .. 或 ~，指定了步长时也可用 -（否则按减法计算）
+    let number = r"\d+(?:\.\d+)?(?:bn|k|m|千|万|亿)?";
+    let has_step = text.split_whitespace().any(|w| STEP_WORDS.contains(&w));
+    let separator = if has_step { r"\.\.|~|～|-" } else { r"\.\.|~|～" };
+    let range_re = Regex::new(&format!(r"^({number})\s*(?:{separator})\s*({number})(?:[^a-z]|$)")).unwrap();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
 
-    let num_str = num_part.first().cloned().unwrap_or_default();
-    let rest = rest.into_iter()
-        .filter(|s| !s.is_empty())
-        .map(String::from)
+    while let Some(c) = text[pos..].chars().next() {
+        let rest = &text[pos..];
+        let token = |kind, text: &str| Token { kind, text: text.to_string(), offset: pos };
+
+        if c.is_whitespace() {
+            pos += c.len_utf8();
+        } else if let Some(caps) = date_re.captures(rest) {
+            tokens.push(token(TokenKind::Date, &caps[1]));
+            pos += caps.get(0).unwrap().end();
+        } else if let Some(caps) = range_re.captures(rest) {
+            let end = caps.get(2).unwrap().end();
+            tokens.push(token(TokenKind::Range, &format!("{}..{}", &caps[1], &caps[2])));
+            pos += end;
+        } else if let Some((code, len)) = match_symbol(rest, config) {
+            tokens.push(token(TokenKind::Currency, &code));
+            pos += len;
+        } else if c == '+' {
+            tokens.push(token(TokenKind::Plus, "+"));
+            pos += 1;
+        } else if let end @ 1.. = expression_end(rest) {
+            tokens.push(token(TokenKind::Amount, rest[..end].trim()));
+            pos += end;
+        } else {
+            // 货币词到空白、+ 或数字为止
+            // 无法识别的数字（如 1.2.3）至少前进一个字符，避免死循环
+            let len = rest
+                .find(|c: char| c.is_whitespace() || c == '+' || c.is_ascii_digit())
+                .unwrap_or(rest.len())
+                .max(c.len_utf8());
+            push_words(&rest[..len], pos, &mut tokens, config);
+            pos += len;
+        }
+    }
+
+    // 步长关键词只用于范围，否则按货币词处理，如 100 usd by 为白俄罗斯
+    if !tokens.iter().any(|t| t.kind == TokenKind::Range) {
+        for token in tokens.iter_mut().filter(|t| t.kind == TokenKind::Step) {
+            token.kind = TokenKind::Currency;
+        }
+    }
+
+    // 输入以连接词结束（后面没有空格）时按国家/地区代码处理，如 100 usd in 为印度
+    // 自动补全的 "100 USD to " 以空格结尾，仍是连接词
+    if let Some(last) = tokens.last_mut() {
+        let is_country_code = COUNTRIES.iter().any(|(alpha2, ..)| alpha2.eq_ignore_ascii_case(&last.text));
+        if last.kind == TokenKind::Connector && last.offset + last.text.len() == text.len() && is_country_code {
+            last.kind = TokenKind::Currency;
+        }
+    }
+    tokens.retain(|t| t.kind != TokenKind::Connector);
+
+    // 前面没有货币的单位接到后面的货币上，如 3.45/gal usd cny 即 3.45 usd/gal cny
+    let mut i = 0;
+    while i + 1 < tokens.len() {
+        let next = &tokens[i + 1];
+        if tokens[i].text.starts_with('/') && next.kind == TokenKind::Currency && !next.text.contains('/') {
+            let unit = tokens.remove(i);
+            tokens[i].text.push_str(&unit.text);
+        } else {
+            i += 1;
+        }
+    }
+
+    merge_english_names(text, &mut tokens);
+    tokens
+}
+
+// 合并以空格分开的英文名称，如 swiss franc、hong kong dollar、el salvador，第二个词允许拼写错误
+fn merge_english_names(text: &str, tokens: &mut Vec<Token>) {
+    let mut i = 0;
+    while i + 1 < tokens.len() {
+        let (a, b) = (&tokens[i], &tokens[i + 1]);
+        // 两个词在原文中只隔空白（符号转换的代码不算）
+        let adjacent = text
+            .get(a.offset..b.offset)
+            .and_then(|s| s.strip_prefix(a.text.as_str()))
+            .is_some_and(|between| between.trim().is_empty());
+        let phrase = format!("{} {}", a.text, b.text);
+        let currency_names = CURRENCY_NAMES_EN.iter().flat_map(|(_, (country, coin))| [*country, *coin]);
+        let country_names = COUNTRIES.iter().map(|(_, name, ..)| *name);
+        let is_name = currency_names.chain(country_names).any(|name| {
+            let name = name.to_lowercase();
+            let starts = |word: &str| name.starts_with(word) || name.contains(&format!(" {}", word));
+            // 第一个词须拼写正确，避免把 usd dolar 之类的两种货币合并
+            starts(&phrase) || starts(&a.text) && is_typo(&phrase, &name)
+        });
+        if a.kind == TokenKind::Currency
+            && b.kind == TokenKind::Currency
+            && a.text.is_ascii()
+            && !a.text.contains('/')
+            && adjacent
+            && is_name
+        {
+            tokens[i].text = phrase;
+            tokens.remove(i + 1);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+// 去掉连接词，货币符号转为代码，其他标点视为分隔符；/ 后跟计量单位时表示单价，如 usd/gal
+fn push_words(word: &str, offset: usize, tokens: &mut Vec<Token>, config: &Config) {
+    // 替换为等长的空格以保留位置
+    let cleaned = CONNECTOR_ARROWS
+        .iter()
+        .fold(word.to_string(), |s, arrow| s.replace(arrow, &" ".repeat(arrow.len())));
+    let cleaned: String = cleaned
+        .char_indices()
+        .map(|(i, c)| match c {
+            '/' if is_unit(&cleaned[i + 1..]) => c,
+            '.' | '-' => c,
+            c if c.is_ascii_punctuation() => ' ',
+            c => c,
+        })
         .collect();
 
-    (num_str.to_string(), rest)
+    for piece in cleaned.split_whitespace().flat_map(split_connectors) {
+        let mut start = piece.as_ptr() as usize - cleaned.as_ptr() as usize;
+        let mut piece = piece;
+        if CONNECTOR_WORDS.contains(&piece) {
+            tokens.push(Token { kind: TokenKind::Connector, text: piece.to_string(), offset: offset + start });
+            continue;
+        }
+        if STEP_WORDS.contains(&piece) || TABLE_WORDS.contains(&piece) {
+            let kind = if STEP_WORDS.contains(&piece) { TokenKind::Step } else { TokenKind::Table };
+            tokens.push(Token { kind, text: piece.to_string(), offset: offset + start });
+            continue;
+        }
+        if let Some((code, len)) = match_symbol(piece, config) {
+            tokens.push(Token { kind: TokenKind::Currency, text: code, offset: offset + start });
+            piece = &piece[len..];
+            start += len;
+        }
+        // 单位接在前一个货币之后，中间可隔金额，如 $/gal、$3.45/gal
+        if let Some(unit) = piece.strip_prefix('/') {
+            let last = tokens
+                .iter_mut()
+                .rev()
+                .take_while(|t| matches!(t.kind, TokenKind::Currency | TokenKind::Amount))
+                .find(|t| t.kind == TokenKind::Currency)
+                .filter(|t| !t.text.contains('/'));
+            match last {
+                Some(last) => last.text = format!("{}/{}", last.text, unit),
+                // 前面没有货币时暂留 /单位，由 tokenize 接到后面的货币上
+                None => tokens.push(Token { kind: TokenKind::Currency, text: piece.to_string(), offset: offset + start }),
+            }
+            continue;
+        }
+        if !piece.is_empty() {
+            tokens.push(Token { kind: TokenKind::Currency, text: piece.to_string(), offset: offset + start });
+        }
+    }
+}
+
+// 解析输入
+//
+// 语法：项 (+ 项)* [连接词] 目标货币*
+//   项：[符号]金额[符号] 源货币，金额和货币可前后互换
+//   连接词：to、in、into、as、->、=>、→、=，以及可与货币名连写的 换、兑、转、兑换、换成 等
+//   日期：@、on 或 at 后跟 yyyy-mm-dd
+//   对照表：起始..结束 [step 步长] 代替金额，或 table 按常用档位列出
+// 多项时每项都须有金额和货币，并且须指定目标货币
+pub fn parse_query(input: &str, config: &Config) -> Result<Query, ParseError> {
+    let normalized = normalize_input(input, config);
+    let tokens = tokenize(&normalized.text, config);
+    if tokens.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let mut query = Query::default();
+    let mut term = Term::default();
+    let mut term_pos = None; // 当前项的起始位置
+    let mut currencies: Vec<CurrencyRef> = Vec::new();
+    let mut step_pos = None; // 等待步长的关键词位置
+
+    for token in tokens {
+        let pos = normalized.position(token.offset);
+        if token.kind != TokenKind::Plus {
+            term_pos.get_or_insert(pos);
+        }
+        match token.kind {
+            TokenKind::Amount if step_pos.is_some() => {
+                step_pos = None;
+                let step = evaluate(&token.text)
+                    .map_err(|message| ParseError::InvalidAmount { message, pos })?;
+                let Some(Table::Range { start, end, step: range_step }) = &mut query.table else {
+                    return Err(table_error("步长只能用于范围，如 100..1000 usd cny step 100", pos));
+                };
+                if step <= 0.0 {
+                    return Err(table_error("步长须大于 0", pos));
+                }
+                if (*end - *start) / step > MAX_TABLE_ROWS as f64 {
+                    return Err(table_error(&format!("对照表最多 {} 行", MAX_TABLE_ROWS), pos));
+                }
+                *range_step = Some(step);
+            }
+            TokenKind::Amount => {
+                if term.amount.is_some() || query.table.is_some() {
+                    return Err(ParseError::UnexpectedAmount { text: token.text, pos });
+                }
+                let value = evaluate(&token.text)
+                    .map_err(|message| ParseError::InvalidAmount { message, pos })?;
+                term.amount = Some(Amount::new(value, &token.text));
+            }
+            TokenKind::Range => {
+                if term.amount.is_some() || query.table.is_some() {
+                    return Err(ParseError::UnexpectedAmount { text: token.text, pos });
+                }
+                let (start, end) = token.text.split_once("..").unwrap_or_default();
+                let start = evaluate(start).map_err(|message| ParseError::InvalidAmount { message, pos })?;
+                let end = evaluate(end).map_err(|message| ParseError::InvalidAmount { message, pos })?;
+                if start >= end {
+                    return Err(table_error("范围须从小到大，如 100..1000", pos));
+                }
+                query.table = Some(Table::Range { start, end, step: None });
+            }
+            TokenKind::Step => step_pos = Some(pos),
+            TokenKind::Table => {
+                query.table.get_or_insert(Table::Preset);
+            }
+            TokenKind::Currency if token.text.starts_with('/') => {
+                return Err(unit_error("单位前缺少货币，如 3.45 usd/gal cny/l", pos));
+            }
+            TokenKind::Currency => currencies.push(currency_ref(&token.text, pos)),
+            TokenKind::Plus => {
+                // 结束一项：须有金额和唯一的货币
+                if term.amount.is_none() {
+                    return Err(ParseError::MissingAmount { pos: term_pos.unwrap_or(pos) });
+                }
+                match currencies.as_slice() {
+                    [source] => term.source = Some(source.clone()),
+                    [] => return Err(ParseError::MissingCurrency { pos }),
+                    [_, extra, ..] => return Err(ParseError::unknown_currency(extra)),
+                }
+                query.terms.push(std::mem::take(&mut term));
+                currencies.clear();
+                term_pos = None;
+            }
+            TokenKind::Date => {
+                let date = NaiveDate::parse_from_str(&token.text, "%Y-%m-%d")
+                    .map_err(|_| ParseError::InvalidDate { text: token.text.clone(), pos })?;
+                query.date = Some(date);
+            }
+            TokenKind::Connector => {} // 已在拆分时去掉
+        }
+    }
+
+    if let Some(pos) = step_pos {
+        return Err(table_error("缺少步长", pos));
+    }
+
+    let mut currencies = currencies.into_iter();
+    term.source = currencies.next();
+    query.targets = currencies.collect();
+
+    // 多币种求和的最后一项
+    if !query.terms.is_empty() {
+        let end = normalized.position(normalized.text.len());
+        if term.amount.is_none() {
+            return Err(ParseError::MissingAmount { pos: term_pos.unwrap_or(end) });
+        }
+        if term.source.is_none() {
+            return Err(ParseError::MissingCurrency { pos: end });
+        }
+        if query.targets.is_empty() {
+            return Err(ParseError::MissingTarget);
+        }
+        if query.table.is_some() {
+            return Err(table_error("多币种求和不支持对照表", term_pos.unwrap_or(end)));
+        }
+    }
+    query.terms.push(term);
+    check_units(&mut query)?;
+
+    Ok(query)
+}
+
+// / 之后是否为计量单位，单位后须为词尾、标点或中文连接词，避免把 人民币/日元 当作每日单价
+fn is_unit(rest: &str) -> bool {
+    unit_prefix_len(rest).is_some_and(|len| {
+        let after = &rest[len..];
+        after.is_empty()
+            || after.starts_with(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+            || CONNECTORS_CN.iter().any(|c| after.starts_with(c))
+    })
+}
+
+// 货币词转为货币引用，/ 后为计量单位
+fn currency_ref(text: &str, pos: usize) -> CurrencyRef {
+    match text.split_once('/') {
+        Some((code, unit)) => CurrencyRef { unit: find_unit(unit), ..CurrencyRef::new(code, pos) },
+        None => CurrencyRef::new(text, pos),
+    }
+}
+
+// 单价须指定源货币的单位，目标未写单位时沿用源单位，两者须为同一种量
+fn check_units(query: &mut Query) -> Result<(), ParseError> {
+    let currencies = query.terms.iter().filter_map(|t| t.source.as_ref()).chain(&query.targets);
+    let Some(pos) = currencies.filter(|c| c.unit.is_some()).map(|c| c.pos).next() else {
+        return Ok(());
+    };
+    if query.terms.len() > 1 {
+        return Err(unit_error("多币种求和不支持单价", pos));
+    }
+    if query.table.is_some() {
+        return Err(unit_error("对照表不支持单价", pos));
+    }
+
+    let source = query.term().source.as_ref().ok_or(unit_error("缺少货币", pos))?;
+    let Some(src_unit) = source.unit else {
+        return Err(unit_error("源货币缺少单位，如 3.45 usd/gal cny/l", source.pos));
+    };
+    if query.targets.is_empty() {
+        return Err(unit_error("请输入目标单价，如 3.45 usd/gal cny/l", source.pos));
+    }
+    for target in &mut query.targets {
+        let unit = *target.unit.get_or_insert(src_unit);
+        if unit.dimension != src_unit.dimension {
+            let message = format!(
+                "{}是{}单位，{}是{}单位，不能换算",
+                src_unit.symbol,
+                src_unit.dimension.name(),
+                unit.symbol,
+                unit.dimension.name()
+            );
+            return Err(unit_error(&message, target.pos));
+        }
+    }
+    Ok(())
+}
+
+fn unit_error(message: &str, pos: usize) -> ParseError {
+    ParseError::InvalidUnit { message: message.to_string(), pos }
+}
+
+fn table_error(message: &str, pos: usize) -> ParseError {
+    ParseError::InvalidTable { message: message.to_string(), pos }
 }
 
 
 #[cfg(test)]
 mod tests {
-    use crate::parser::parse_input;
+    use crate::config::Config;
+    use crate::parser::parse_query;
+    use crate::query::{ParseError, Table};
+
+    // 查询的金额和全部货币（源货币在前）
+    fn summary(input: &str, config: &Config) -> (Option<f64>, Vec<String>) {
+        let query = parse_query(input, config).unwrap();
+        let term = query.term();
+        let currencies = term.source.iter().chain(&query.targets).map(|c| c.text.clone()).collect();
+        (term.amount.as_ref().map(|a| a.value), currencies)
+    }
 
     #[test]
-    fn test_parse_input() {
+    fn test_parse_query() {
+        let config = Config::default();
+
         // 带小数点的紧凑格式
-        assert_eq!(
-            parse_input("14.1USD"),
-            ("14.1".into(), vec!["usd".into()])
-        );
+        assert_eq!(summary("14.1USD", &config), (Some(14.1), vec!["usd".into()]));
 
         // 带目标货币的紧凑格式
-        assert_eq!(
-            parse_input("100.5EUR CNY"),
-            ("100.5".into(), vec!["eur".into(), "cny".into()])
-        );
+        assert_eq!(summary("100.5EUR CNY", &config), (Some(100.5), vec!["eur".into(), "cny".into()]));
 
         // 常规带空格格式
+        assert_eq!(summary("123.45 gbp to jpy", &config), (Some(123.45), vec!["gbp".into(), "jpy".into()]));
+
+        // 混合格式
+        assert_eq!(summary("500usd,cny", &config), (Some(500.0), vec!["usd".into(), "cny".into()]));
+
+        // 算术表达式，原表达式用于回显
+        let query = parse_query("(120+35.5)*1.08 usd cny", &config).unwrap();
+        assert_eq!(query.term().amount.as_ref().unwrap().expression.as_deref(), Some("(120+35.5)*1.08"));
+        assert_eq!(summary("3x 49.99 eur", &config), (Some(149.97), vec!["eur".into()]));
+
+        // 数量级后缀
+        assert_eq!(summary("2.5K USD", &config), (Some(2500.0), vec!["usd".into()]));
+        assert_eq!(summary("jpy 3亿", &config), (Some(3e8), vec!["jpy".into()]));
+
+        // 货币符号
+        assert_eq!(summary("$120 cny", &config), (Some(120.0), vec!["usd".into(), "cny".into()]));
+        assert_eq!(summary("HK$300", &config), (Some(300.0), vec!["hkd".into()]));
+        assert_eq!(summary("100¥ to €", &config), (Some(100.0), vec!["cny".into(), "eur".into()]));
+
+        // 金额在货币之后或省略金额
+        assert_eq!(summary("usd 100 cny", &config), (Some(100.0), vec!["usd".into(), "cny".into()]));
+        assert_eq!(summary("cny to usd 500", &config), (Some(500.0), vec!["cny".into(), "usd".into()]));
+        assert_eq!(summary("usd cny", &config), (None, vec!["usd".into(), "cny".into()]));
+
+        // 多个单词的英文名称
+        assert_eq!(summary("100 swiss franc in hong kong dollar", &config), (Some(100.0), vec!["swiss franc".into(), "hong kong dollar".into()]));
+        assert_eq!(summary("100 swiss frnc cny", &config), (Some(100.0), vec!["swiss frnc".into(), "cny".into()]));
+        assert_eq!(summary("100 usd euro", &config), (Some(100.0), vec!["usd".into(), "euro".into()]));
+        assert_eq!(summary("100 eur el salvador", &config), (Some(100.0), vec!["eur".into(), "el salvador".into()]));
+
+        // 连接词
+        assert_eq!(summary("100 usd -> cny", &config), (Some(100.0), vec!["usd".into(), "cny".into()]));
+        assert_eq!(summary("100usd=cny", &config), (Some(100.0), vec!["usd".into(), "cny".into()]));
+        // 末尾的连接词和没有范围时的步长关键词可作国家/地区代码
+        assert_eq!(summary("100 usd in", &config), (Some(100.0), vec!["usd".into(), "in".into()]));
+        assert_eq!(summary("100 usd in ", &config), (Some(100.0), vec!["usd".into()]));
+        assert_eq!(summary("100 usd by", &config), (Some(100.0), vec!["usd".into(), "by".into()]));
+        assert_eq!(summary("100美元换人民币", &config), (Some(100.0), vec!["美元".into(), "人民币".into()]));
         assert_eq!(
-            parse_input("123.45 gbp to jpy"),
-            ("123.45".into(), vec!["gbp".into(), "to".into(), "jpy".into()])
+            summary("100 可兑换马克兑换成欧元", &config),
+            (Some(100.0), vec!["可兑换马克".into(), "欧元".into()])
         );
+        assert_eq!(summary("100 usd to ", &config), (Some(100.0), vec!["usd".into()]));
 
-        // 混合格式
+        // 千位分隔符和小数逗号
+        assert_eq!(summary("1,234.56 usd", &config), (Some(1234.56), vec!["usd".into()]));
+        assert_eq!(summary("1.234,56 eur", &config), (Some(1234.56), vec!["eur".into()]));
+        assert_eq!(summary("1 234,56 sek", &config), (Some(1234.56), vec!["sek".into()]));
+        assert_eq!(summary("12'345.00 chf", &config), (Some(12345.0), vec!["chf".into()]));
+        assert_eq!(summary("1,234 usd", &config), (Some(1234.0), vec!["usd".into()]));
+        assert_eq!(summary("1,5 eur", &config), (Some(1.5), vec!["eur".into()]));
+        let comma = Config { decimal_separator: Some(','), ..Config::default() };
+        assert_eq!(summary("1,234 eur", &comma), (Some(1.234), vec!["eur".into()]));
+        assert_eq!(summary("1.234 eur", &comma), (Some(1234.0), vec!["eur".into()]));
+
+        // 中文数字
+        assert_eq!(summary("两万五 人民币", &config), (Some(25000.0), vec!["人民币".into()]));
+
+        // 日期
+        let query = parse_query("100 usd cny on 2024-01-31", &config).unwrap();
+        assert_eq!(query.date.map(|d| d.to_string()), Some("2024-01-31".into()));
+        assert_eq!(query.targets.len(), 1);
+        assert!(parse_query("100 usd cny @2024-01-31", &config).unwrap().date.is_some());
+        // 没有 @、on 前缀时按减法计算
+        let query = parse_query("2000-12-5 usd cny", &config).unwrap();
+        assert_eq!(query.date, None);
+        assert_eq!(query.term().amount.as_ref().map(|a| a.value), Some(1983.0));
+    }
+
+    #[test]
+    fn test_parse_sum() {
+        let config = Config::default();
+
+        let query = parse_query("100 usd + 50 eur + 2000 jpy in cny", &config).unwrap();
+        let terms: Vec<_> = query
+            .terms
+            .iter()
+            .map(|t| (t.amount.as_ref().unwrap().value, t.source.as_ref().unwrap().text.as_str()))
+            .collect();
+        assert_eq!(terms, vec![(100.0, "usd"), (50.0, "eur"), (2000.0, "jpy")]);
+        assert_eq!(query.targets[0].text, "cny");
+
+        // 项内的算术表达式和货币符号
+        let query = parse_query("1+2 usd + €(3+4) to cny", &config).unwrap();
+        assert_eq!(query.terms.len(), 2);
+        assert_eq!(query.terms[1].amount.as_ref().unwrap().value, 7.0);
+
+        // 单一货币的表达式不是求和
+        assert_eq!(parse_query("100 + 50 usd", &config).unwrap().terms.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_table() {
+        let config = Config::default();
+
+        let query = parse_query("100..1000 usd cny step 100", &config).unwrap();
+        assert_eq!(query.table, Some(Table::Range { start: 100.0, end: 1000.0, step: Some(100.0) }));
+        assert_eq!(query.table.unwrap().amounts(1.0).len(), 10);
+
+        // 指定步长时 - 表示范围，否则按减法计算
+        let query = parse_query("100-1000 usd cny by 100", &config).unwrap();
+        assert_eq!(query.table.unwrap().amounts(1.0)[1], 200.0);
+        assert_eq!(parse_query("1000-100 usd cny", &config).unwrap().term().amount.as_ref().unwrap().value, 900.0);
+
+        let query = parse_query("usd cny table", &config).unwrap();
+        assert_eq!(query.table, Some(Table::Preset));
+        assert_eq!(query.targets[0].text, "cny");
+
+        assert!(parse_query("1000..100 usd cny", &config).is_err());
+        assert!(parse_query("100..1000 usd cny step 1", &config).is_err());
+        assert!(parse_query("100..1000 usd cny step", &config).is_err());
+    }
+
+    #[test]
+    fn test_parse_units() {
+        let config = Config::default();
+
+        let query = parse_query("3.45 usd/gal cny/l", &config).unwrap();
+        assert_eq!(query.term().source.as_ref().unwrap().unit.unwrap().symbol, "gal");
+        assert_eq!(query.targets[0].text, "cny");
+        assert_eq!(query.targets[0].unit.unwrap().symbol, "L");
+
+        // 中文单位和连接词，目标未写单位时沿用源单位
+        let query = parse_query("12欧元/公斤换人民币/斤", &config).unwrap();
+        assert_eq!(query.targets[0].unit.unwrap().symbol, "斤");
+        let query = parse_query("$3.45/gal cny", &config).unwrap();
+        assert_eq!(query.targets[0].unit.unwrap().symbol, "gal");
+
+        // / 后不是单位时仍作分隔符
+        assert_eq!(summary("100 usd/cny", &config), (Some(100.0), vec!["usd".into(), "cny".into()]));
+        assert_eq!(summary("100 人民币/日元", &config), (Some(100.0), vec!["人民币".into(), "日元".into()]));
+
+        // 时间单位
+        let query = parse_query("45 usd/hour cny/月", &config).unwrap();
+        assert_eq!(query.targets[0].unit.unwrap().symbol, "月");
+
+        assert!(matches!(parse_query("3.45 usd/gal cny/kg", &config), Err(ParseError::InvalidUnit { pos: 14, .. })));
+        assert!(matches!(parse_query("3 usd cny/l", &config), Err(ParseError::InvalidUnit { .. })));
+
+        // 单位写在货币前时接到后面的货币上
+        let query = parse_query("3.45/gal usd cny", &config).unwrap();
+        assert_eq!(query.term().source.as_ref().unwrap().text, "usd");
+        assert_eq!(query.term().source.as_ref().unwrap().unit.unwrap().symbol, "gal");
+        assert_eq!(query.targets[0].unit.unwrap().symbol, "gal");
+        assert!(matches!(parse_query("/gal", &config), Err(ParseError::InvalidUnit { .. })));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let config = Config::default();
+
+        assert_eq!(parse_query("  ", &config), Err(ParseError::Empty));
+        assert_eq!(parse_query("100 usd + 50 eur", &config), Err(ParseError::MissingTarget));
+        assert_eq!(parse_query("100 usd + eur in cny", &config), Err(ParseError::MissingAmount { pos: 11 }));
+        assert_eq!(parse_query("100 usd + 50", &config), Err(ParseError::MissingCurrency { pos: 13 }));
         assert_eq!(
-            parse_input("500usd,cny"),
-            ("500".into(), vec!["usd".into(), "cny".into()])
+            parse_query("1/0 usd", &config),
+            Err(ParseError::InvalidAmount { message: "除数不能为 0".into(), pos: 1 })
         );
+        assert_eq!(
+            parse_query("100 usd 200 cny", &config),
+            Err(ParseError::UnexpectedAmount { text: "200".into(), pos: 9 })
+        );
+
+        // 位置按原输入计算，不受数字规范化影响
+        let query = parse_query("1,234.56 usx", &config).unwrap();
+        assert_eq!(query.term().source.as_ref().unwrap().pos, 10);
+        let query = parse_query("100美元换人民币", &config).unwrap();
+        assert_eq!(query.targets[0].pos, 7);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use serde_json::Value;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+// 外部命令汇率源
+//
+// 命令通过 `sh -c` 执行，须在标准输出打印如下 JSON：
+//
+//     {"base": "USD", "rates": {"CNY": 7.1, "EUR": 0.92, "JPY": 149.5}}
+//
+// - rates：必填，货币代码（3 位字母）到汇率的映射，汇率为 1 单位 base 可兑换的数量，须为正数
+// - base：可选，基准货币代码，缺省为 USD；rates 中未列出时按 1 补齐
+//
+// 命令以非零状态退出、超时或输出不符合上述格式时视为失败
+pub fn run_rates_command(command: &str, timeout: Duration) -> Result<HashMap<String, f64>, String> {
+    // 单独的进程组，超时时连同命令启动的后台进程一起结束
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()
+        .map_err(|e| format!("无法启动汇率命令: {}", e))?;
+    let timed_out = |child: &mut Child| {
+        kill_group(child);
+        format!("汇率命令执行超时（{} 秒）", timeout.as_secs_f64())
+    };
+
+    // 在后台读取输出，避免管道写满导致子进程阻塞；
+    // 后台进程可能一直占用输出管道，因此经通道按剩余时间等待
+    let mut stdout = child.stdout.take().ok_or("无法读取汇率命令输出")?;
+    let mut stderr = child.stderr.take().ok_or("无法读取汇率命令输出")?;
+    let (out_sender, out_receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = out_sender.send(stdout.read_to_string(&mut buf).map(|_| buf));
+    });
+    let (err_sender, err_receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = err_sender.send(stderr.read_to_string(&mut buf).map(|_| buf));
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            return Err(timed_out(&mut child));
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let output = match out_receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        Ok(output) => output.map_err(|e| e.to_string())?,
+        Err(RecvTimeoutError::Timeout) => return Err(timed_out(&mut child)),
+        Err(RecvTimeoutError::Disconnected) => return Err("无法读取汇率命令输出".to_string()),
+    };
+    let errors = err_receiver
+        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        .ok()
+        .and_then(Result::ok)
+        .unwrap_or_default();
+
+    if !status.success() {
+        return Err(format!("汇率命令执行失败（{}）: {}", status, errors.trim()));
+    }
+
+    parse_rates_json(&output)
+}
+
+// 结束命令所在的整个进程组
+fn kill_group(child: &mut Child) {
+    let _ = Command::new("kill")
+        .args(["-TERM", "--", &format!("-{}", child.id())])
+        .stderr(Stdio::null())
+        .status();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+// 固定汇率文件
+//
+// 扩展名为 .csv 时按 CSV 解析，每行 `代码,汇率`，允许表头和 # 注释，基准货币为 USD；
+// 其余按外部命令的 JSON 格式解析
+pub fn load_rates_file(path: &Path) -> Result<HashMap<String, f64>, String> {
+    let data = fs::read_to_string(path).map_err(|e| format!("无法读取汇率文件 {}: {}", path.display(), e))?;
+    let is_csv = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+
+    if is_csv {
+        parse_rates_csv(&data)
+    } else {
+        parse_rates_json(&data)
+    }
+}
+
+// 解析并校验汇率 CSV
+pub fn parse_rates_csv(data: &str) -> Result<HashMap<String, f64>, String> {
+    let mut rates = Vec::new();
+    for (index, line) in data.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [code, rate] = fields.as_slice() else {
+            return Err(format!("汇率文件第 {} 行格式错误: {}", index + 1, line));
+        };
+        match rate.parse::<f64>() {
+            Ok(rate) => rates.push((code.to_string(), Some(rate))),
+            // 首行允许表头
+            Err(_) if index == 0 => continue,
+            Err(_) => return Err(format!("汇率文件第 {} 行汇率无效: {}", index + 1, rate)),
+        }
+    }
+
+    validate_rates("USD", rates)
+}
+
+// 解析并校验汇率 JSON
+pub fn parse_rates_json(data: &str) -> Result<HashMap<String, f64>, String> {
+    let data: Value = serde_json::from_str(data).map_err(|e| format!("汇率数据不是有效的 JSON: {}", e))?;
+    let rates = data["rates"].as_object().ok_or("汇率数据缺少 rates 对象")?;
+
+    let base = match &data["base"] {
+        Value::Null => "USD",
+        Value::String(code) => code.as_str(),
+        other => return Err(format!("无效的基准货币: {}", other)),
+    };
+
+    validate_rates(base, rates.iter().map(|(code, rate)| (code.clone(), rate.as_f64())))
+}
+
+// 校验货币代码和汇率，并统一换算为 1 单位基准货币
+fn validate_rates(
+    base: &str,
+    rates: impl IntoIterator<Item = (String, Option<f64>)>,
+) -> Result<HashMap<String, f64>, String> {
+    let base = normalize_code(base).ok_or(format!("无效的基准货币: {}", base))?;
+
+    let mut result = HashMap::new();
+    for (code, rate) in rates {
+        let code = normalize_code(&code).ok_or(format!("无效的货币代码: {}", code))?;
+        let rate = rate
+            .filter(|r| r.is_finite() && *r > 0.0)
+            .ok_or(format!("{} 的汇率无效", code))?;
+        result.insert(code, rate);
+    }
+
+    if result.is_empty() {
+        return Err("汇率数据为空".to_string());
+    }
+
+    let base_rate = *result.entry(base).or_insert(1.0);
+    for rate in result.values_mut() {
+        *rate /= base_rate;
+    }
+
+    Ok(result)
+}
+
+fn normalize_code(code: &str) -> Option<String> {
+    let code = code.trim();
+    if code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) {
+        Some(code.to_ascii_uppercase())
+    } else {
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::provider::{parse_rates_csv, parse_rates_json, run_rates_command};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_parse_rates_json() {
+        // 非 USD 基准统一换算
+        let rates = parse_rates_json(r#"{"base": "eur", "rates": {"USD": 2, "CNY": 16}}"#).unwrap();
+        assert_eq!(rates["EUR"], 1.0);
+        assert_eq!(rates["CNY"], 16.0);
+
+        // 缺少 rates、非法代码与非正汇率
+        assert!(parse_rates_json(r#"{"base": "USD"}"#).is_err());
+        assert!(parse_rates_json(r#"{"rates": {"US": 1}}"#).is_err());
+        assert!(parse_rates_json(r#"{"rates": {"CNY": -7.1}}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_rates_csv() {
+        let rates = parse_rates_csv("code,rate\n# 测试数据\nCNY, 7.1\njpy,150\n").unwrap();
+        assert_eq!(rates["CNY"], 7.1);
+        assert_eq!(rates["JPY"], 150.0);
+        assert_eq!(rates["USD"], 1.0);
+
+        assert!(parse_rates_csv("CNY,7.1\nEUR\n").is_err());
+        assert!(parse_rates_csv("CNY,7.1\nEUR,abc\n").is_err());
+    }
+
+    #[test]
+    fn test_run_rates_command() {
+        let rates = run_rates_command(r#"echo '{"rates": {"CNY": 7.1}}'"#, Duration::from_secs(5)).unwrap();
+        assert_eq!(rates["USD"], 1.0);
+        assert_eq!(rates["CNY"], 7.1);
+
+        assert!(run_rates_command("sleep 5", Duration::from_millis(100)).is_err());
+        assert!(run_rates_command("exit 1", Duration::from_secs(5)).is_err());
+
+        // 后台进程占用输出管道时也按时返回
+        let start = Instant::now();
+        let _ = run_rates_command(r#"(sleep 6) & echo '{"rates": {"CNY": 7}}'"#, Duration::from_secs(1));
+        assert!(start.elapsed() < Duration::from_secs(3));
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::fmt;
+use chrono::NaiveDate;
+use crate::model::{Amount, CurrencyInfo, Retirement};
+use crate::units::Unit;
+
+// 解析后的查询
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    pub terms: Vec<Term>,           // 金额和源货币，多币种求和时有多项
+    pub targets: Vec<CurrencyRef>,  // 目标货币，按输入顺序
+    pub date: Option<NaiveDate>,    // 查询日期，如 @2024-01-01
+    pub table: Option<Table>,       // 对照表，如 100..1000 usd cny step 100
+}
+
+// 一项金额及其货币
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Term {
+    pub amount: Option<Amount>,
+    pub source: Option<CurrencyRef>,
+}
+
+// 输入中的货币词，pos 为在原输入中的字符位置（从 1 开始），单价时带计量单位，如 usd/gal
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrencyRef {
+    pub text: String,
+    pub pos: usize,
+    pub unit: Option<&'static Unit>,
+}
+
+impl CurrencyRef {
+    pub fn new(text: &str, pos: usize) -> Self {
+        Self {
+            text: text.to_string(),
+            pos,
+            unit: None,
+        }
+    }
+}
+
+// 对照表的金额档位
+#[derive(Debug, Clone, PartialEq)]
+pub enum Table {
+    Range { start: f64, end: f64, step: Option<f64> }, // 未指定步长时均分为 10 档
+    Preset,                                            // 常用档位，乘以输入的金额
+}
+
+// 常用档位
+const PRESET_TIERS: &[f64] = &[1.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 5000.0, 10000.0];
+const DEFAULT_STEPS: f64 = 10.0;
+pub const MAX_TABLE_ROWS: usize = 100;
+
+impl Table {
+    // 展开为金额列表，base 为预设档位的倍数
+    pub fn amounts(&self, base: f64) -> Vec<f64> {
+        match *self {
+            Table::Range { start, end, step } => {
+                let step = step.unwrap_or((end - start) / DEFAULT_STEPS);
+                // 按档位计算避免累加误差，末尾留出浮点余量
+                let count = ((end - start) / step + 1e-9).floor() as usize;
+                (0..=count.min(MAX_TABLE_ROWS))
+                    .map(|i| ((start + step * i as f64) * 1e10).round() / 1e10)
+                    .collect()
+            }
+            Table::Preset => PRESET_TIERS.iter().map(|tier| tier * base).collect(),
+        }
+    }
+}
+
+impl Query {
+    // 单项查询的金额和源货币
+    pub fn term(&self) -> &Term {
+        static EMPTY: Term = Term { amount: None, source: None };
+        self.terms.first().unwrap_or(&EMPTY)
+    }
+}
+
+// 解析错误，位置为原输入中的字符位置（从 1 开始）
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    Empty,
+    InvalidAmount { message: String, pos: usize },
+    UnexpectedAmount { text: String, pos: usize },
+    MissingAmount { pos: usize },
+    MissingCurrency { pos: usize },
+    MissingTarget,
+    UnknownCurrency { token: String, pos: usize },
+    MissingSuccessor { retirement: &'static Retirement, pos: usize },
+    InvalidDate { text: String, pos: usize },
+    InvalidTable { message: String, pos: usize },
+    InvalidUnit { message: String, pos: usize },
+}
+
+impl ParseError {
+    pub fn unknown_currency(currency: &CurrencyRef) -> Self {
+        ParseError::UnknownCurrency {
+            token: currency.text.clone(),
+            pos: currency.pos,
+        }
+    }
+
+    // 汇率中找不到的货币；停用货币缺少继任货币的汇率时说明原因
+    pub fn missing_currency(currency: &CurrencyRef, currencies: &HashMap<String, CurrencyInfo>) -> Self {
+        match crate::retirement(&currency.text.to_ascii_uppercase()) {
+            Some(retirement) if !currencies.contains_key(retirement.successor) => {
+                ParseError::MissingSuccessor { retirement, pos: currency.pos }
+            }
+            _ => ParseError::unknown_currency(currency),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "输入金额和货币"),
+            ParseError::InvalidAmount { message, pos } => write!(f, "{}（位置 {}）", message, pos),
+            ParseError::UnexpectedAmount { text, pos } => write!(f, "多余的金额 '{}'（位置 {}）", text, pos),
+            ParseError::MissingAmount { pos } => write!(f, "缺少金额（位置 {}）", pos),
+            ParseError::MissingCurrency { pos } => write!(f, "缺少货币（位置 {}）", pos),
+            ParseError::MissingTarget => write!(f, "请输入目标货币，如 100 usd + 50 eur in cny"),
+            ParseError::UnknownCurrency { token, pos } => write!(f, "未知货币 '{}'（位置 {}）", token, pos),
+            ParseError::MissingSuccessor { retirement: r, pos } => write!(
+                f,
+                "{} 已于 {} 由 {} 取代，汇率源缺少 {}（位置 {}）",
+                r.code, r.since, r.successor, r.successor, pos
+            ),
+            ParseError::InvalidDate { text, pos } => write!(f, "无效的日期 '{}'（位置 {}）", text, pos),
+            ParseError::InvalidTable { message, pos } | ParseError::InvalidUnit { message, pos } => {
+                write!(f, "{}（位置 {}）", message, pos)
+            }
+        }
+    }
+}
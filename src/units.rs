@@ -0,0 +1,153 @@
+use crate::config::Config;
+
+// 单价换算的计量单位：体积以升、质量以千克、长度以米、时间以工作小时为基准
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dimension {
+    Volume,
+    Mass,
+    Length,
+    Time,
+}
+
+impl Dimension {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dimension::Volume => "体积",
+            Dimension::Mass => "质量",
+            Dimension::Length => "长度",
+            Dimension::Time => "时间",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Unit {
+    pub symbol: &'static str,          // 显示用符号
+    pub names: &'static [&'static str], // 输入时可用的名称（小写）
+    pub dimension: Dimension,
+    pub factor: Factor,
+}
+
+// 换算为基准单位的倍数；时间单位记为若干小时、天、周或月，按配置的工作时间换算为小时
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Factor {
+    Base(f64),
+    Hours(f64),
+    Days(f64),
+    Weeks(f64),
+    Months(f64),
+}
+
+const WEEKS_PER_YEAR: f64 = 52.0;
+
+// 加仑、夸脱、品脱按美制计
+static UNITS: &[Unit] = &[
+    // 体积
+    Unit { symbol: "L", names: &["l", "liter", "liters", "litre", "litres", "升", "公升"], dimension: Dimension::Volume, factor: Factor::Base(1.0) },
+    Unit { symbol: "mL", names: &["ml", "毫升"], dimension: Dimension::Volume, factor: Factor::Base(0.001) },
+    Unit { symbol: "gal", names: &["gal", "gallon", "gallons", "加仑"], dimension: Dimension::Volume, factor: Factor::Base(3.785411784) },
+    Unit { symbol: "imp gal", names: &["impgal", "英制加仑"], dimension: Dimension::Volume, factor: Factor::Base(4.54609) },
+    Unit { symbol: "qt", names: &["qt", "quart", "夸脱"], dimension: Dimension::Volume, factor: Factor::Base(0.946352946) },
+    Unit { symbol: "pt", names: &["pt", "pint", "品脱"], dimension: Dimension::Volume, factor: Factor::Base(0.473176473) },
+    Unit { symbol: "fl oz", names: &["floz", "液量盎司"], dimension: Dimension::Volume, factor: Factor::Base(0.0295735295625) },
+    Unit { symbol: "bbl", names: &["bbl", "barrel", "桶"], dimension: Dimension::Volume, factor: Factor::Base(158.987294928) },
+    Unit { symbol: "m³", names: &["cbm", "立方米", "立方"], dimension: Dimension::Volume, factor: Factor::Base(1000.0) },
+    // 质量
+    Unit { symbol: "kg", names: &["kg", "kilo", "kilogram", "公斤", "千克"], dimension: Dimension::Mass, factor: Factor::Base(1.0) },
+    Unit { symbol: "g", names: &["g", "gram", "克"], dimension: Dimension::Mass, factor: Factor::Base(0.001) },
+    Unit { symbol: "t", names: &["t", "ton", "tonne", "吨"], dimension: Dimension::Mass, factor: Factor::Base(1000.0) },
+    Unit { symbol: "lb", names: &["lb", "lbs", "pound", "磅"], dimension: Dimension::Mass, factor: Factor::Base(0.45359237) },
+    Unit { symbol: "oz", names: &["oz", "ounce", "盎司"], dimension: Dimension::Mass, factor: Factor::Base(0.028349523125) },
+    Unit { symbol: "ozt", names: &["ozt", "troyoz", "金衡盎司"], dimension: Dimension::Mass, factor: Factor::Base(0.0311034768) },
+    Unit { symbol: "斤", names: &["jin", "斤", "市斤"], dimension: Dimension::Mass, factor: Factor::Base(0.5) },
+    Unit { symbol: "两", names: &["liang", "两"], dimension: Dimension::Mass, factor: Factor::Base(0.05) },
+    // 长度
+    Unit { symbol: "m", names: &["m", "meter", "metre", "米"], dimension: Dimension::Length, factor: Factor::Base(1.0) },
+    Unit { symbol: "cm", names: &["cm", "厘米"], dimension: Dimension::Length, factor: Factor::Base(0.01) },
+    Unit { symbol: "km", names: &["km", "公里", "千米"], dimension: Dimension::Length, factor: Factor::Base(1000.0) },
+    Unit { symbol: "in", names: &["in", "inch", "英寸"], dimension: Dimension::Length, factor: Factor::Base(0.0254) },
+    Unit { symbol: "ft", names: &["ft", "foot", "feet", "英尺"], dimension: Dimension::Length, factor: Factor::Base(0.3048) },
+    Unit { symbol: "yd", names: &["yd", "yard", "码"], dimension: Dimension::Length, factor: Factor::Base(0.9144) },
+    Unit { symbol: "mi", names: &["mi", "mile", "英里"], dimension: Dimension::Length, factor: Factor::Base(1609.344) },
+    Unit { symbol: "尺", names: &["chi", "尺"], dimension: Dimension::Length, factor: Factor::Base(1.0 / 3.0) },
+    // 时间，按工作时间计，如时薪换算月薪
+    Unit { symbol: "小时", names: &["hour", "hours", "hr", "h", "小时", "时"], dimension: Dimension::Time, factor: Factor::Hours(1.0) },
+    Unit { symbol: "天", names: &["day", "days", "d", "天", "日"], dimension: Dimension::Time, factor: Factor::Days(1.0) },
+    Unit { symbol: "周", names: &["week", "weeks", "wk", "周", "星期"], dimension: Dimension::Time, factor: Factor::Weeks(1.0) },
+    Unit { symbol: "月", names: &["month", "months", "mo", "月"], dimension: Dimension::Time, factor: Factor::Months(1.0) },
+    Unit { symbol: "年", names: &["year", "years", "yr", "y", "年"], dimension: Dimension::Time, factor: Factor::Weeks(WEEKS_PER_YEAR) },
+];
+
+// 按名称查找单位
+pub fn find_unit(name: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|unit| unit.names.contains(&name))
+}
+
+impl Unit {
+    // 换算为基准单位的倍数，时间单位按配置的每天工时和每周天数计算，每年 52 周
+    pub fn factor(&self, config: &Config) -> f64 {
+        let week = config.hours_per_day * config.days_per_week;
+        match self.factor {
+            Factor::Base(factor) | Factor::Hours(factor) => factor,
+            Factor::Days(days) => days * config.hours_per_day,
+            Factor::Weeks(weeks) => weeks * week,
+            Factor::Months(months) => months * week * WEEKS_PER_YEAR / 12.0,
+        }
+    }
+
+    // 与 other 之间的换算是否依赖工作时间：小时、天与周及更长的单位之间
+    pub fn depends_on_work_time(&self, other: &Unit) -> bool {
+        let short = |unit: &Unit| matches!(unit.factor, Factor::Hours(_) | Factor::Days(_));
+        let long = |unit: &Unit| matches!(unit.factor, Factor::Weeks(_) | Factor::Months(_));
+        (short(self) && long(other)) || (long(self) && short(other))
+    }
+}
+
+// 开头最长的单位名称的长度，名称后不能紧跟字母或数字，如 gal 不匹配 gala
+pub fn unit_prefix_len(input: &str) -> Option<usize> {
+    UNITS
+        .iter()
+        .flat_map(|unit| unit.names.iter())
+        .filter(|name| {
+            input.starts_with(**name) && !input[name.len()..].starts_with(|c: char| c.is_ascii_alphanumeric())
+        })
+        .map(|name| name.len())
+        .max()
+}
+
+// 单价从 src 单位换算到 dst 单位的倍数，如 每加仑 → 每升 为 1 / 3.785
+pub fn price_factor(src: &Unit, dst: &Unit, config: &Config) -> f64 {
+    dst.factor(config) / src.factor(config)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::units::{find_unit, price_factor, unit_prefix_len};
+
+    #[test]
+    fn test_units() {
+        let config = Config::default();
+        let gal = find_unit("gal").unwrap();
+        let liter = find_unit("l").unwrap();
+        assert!((price_factor(gal, liter, &config) - 0.264172).abs() < 1e-6);
+        assert_eq!(price_factor(find_unit("kg").unwrap(), find_unit("斤").unwrap(), &config), 0.5);
+
+        // 时间单位：月付换年付与工时无关，时薪按配置的工时换算
+        let month = find_unit("month").unwrap();
+        assert_eq!(price_factor(month, find_unit("year").unwrap(), &config), 12.0);
+        assert_eq!(price_factor(find_unit("hour").unwrap(), month, &config), 40.0 * 52.0 / 12.0);
+        let config = Config { hours_per_day: 6.0, days_per_week: 4.0, ..Config::default() };
+        assert_eq!(price_factor(find_unit("hour").unwrap(), find_unit("week").unwrap(), &config), 24.0);
+        assert!(find_unit("day").unwrap().depends_on_work_time(month));
+        assert!(!find_unit("week").unwrap().depends_on_work_time(month));
+        assert!(!find_unit("hour").unwrap().depends_on_work_time(find_unit("day").unwrap()));
+
+        assert_eq!(unit_prefix_len("gal cny"), Some(3));
+        assert_eq!(unit_prefix_len("gallon"), Some(6));
+        assert_eq!(unit_prefix_len("gala"), None);
+        assert_eq!(unit_prefix_len("公斤换人民币"), Some(6));
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use crate::config::Language;
+
+const USAGE_FILE: &str = "usage.json";
+const HALF_LIFE_DAYS: f64 = 14.0; // 使用记录的权重每 14 天减半
+
+// 使用记录：用户选择过的货币和货币对，保存在 Alfred 工作流数据目录
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Usage {
+    currencies: HashMap<String, Entry>,
+    pairs: HashMap<String, Entry>, // 键为 "USD CNY"
+    #[serde(skip)]
+    now: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct Entry {
+    count: u32,
+    last_used: i64, // Unix 时间戳（秒）
+}
+
+impl Entry {
+    // 常用度：次数按最近一次使用的时间衰减
+    fn frecency(&self, now: i64) -> f64 {
+        let days = (now - self.last_used).max(0) as f64 / 86400.0;
+        self.count as f64 * 0.5f64.powf(days / HALF_LIFE_DAYS)
+    }
+}
+
+impl Usage {
+    // 读取使用记录，没有目录或文件损坏时为空
+    pub fn load(dir: Option<&Path>) -> Self {
+        let usage: Self = dir
+            .and_then(|dir| fs::read_to_string(usage_path(dir)).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        Self { now: Utc::now().timestamp(), ..usage }
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<(), String> {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(usage_path(dir), json).map_err(|e| e.to_string())
+    }
+
+    // 记录一次换算
+    pub fn record(&mut self, src: &str, dst: &str) {
+        let now = self.now;
+        for key in [src.to_uppercase(), dst.to_uppercase(), pair_key(src, dst)] {
+            let entries = if key.contains(' ') { &mut self.pairs } else { &mut self.currencies };
+            let entry = entries.entry(key).or_default();
+            entry.count += 1;
+            entry.last_used = now;
+        }
+    }
+
+    pub fn currency_score(&self, code: &str) -> f64 {
+        self.currencies.get(code).map_or(0.0, |e| e.frecency(self.now))
+    }
+
+    pub fn pair_score(&self, src: &str, dst: &str) -> f64 {
+        self.pairs.get(&pair_key(src, dst)).map_or(0.0, |e| e.frecency(self.now))
+    }
+}
+
+// 规范化要记录的货币代码：3 位字母转为大写，须是已知的货币，否则为 None
+pub fn normalize_code(code: &str) -> Option<String> {
+    let code = code.trim().to_ascii_uppercase();
+    let valid = code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase());
+    (valid && crate::currency_names(&code, Language::ZhHans).is_some()).then_some(code)
+}
+
+fn usage_path(dir: &Path) -> PathBuf {
+    dir.join(USAGE_FILE)
+}
+
+fn pair_key(src: &str, dst: &str) -> String {
+    format!("{} {}", src.to_uppercase(), dst.to_uppercase())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::usage::{normalize_code, Usage};
+
+    #[test]
+    fn test_usage() {
+        let mut usage = Usage::load(None);
+        usage.record("usd", "cny");
+        usage.record("EUR", "CNY");
+        assert_eq!(usage.currency_score("CNY"), 2.0);
+        assert_eq!(usage.pair_score("USD", "CNY"), 1.0);
+        assert_eq!(usage.pair_score("CNY", "USD"), 0.0);
+
+        // 一个半衰期后权重减半
+        usage.now += 14 * 86400;
+        assert!((usage.currency_score("USD") - 0.5).abs() < 1e-9);
+
+        assert_eq!(normalize_code("usd").as_deref(), Some("USD"));
+        assert_eq!(normalize_code("US"), None);
+        assert_eq!(normalize_code("XYZ"), None);
+        assert_eq!(normalize_code("--date"), None);
+    }
+}